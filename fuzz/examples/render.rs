@@ -0,0 +1,15 @@
+use std::{env, fs};
+
+use arbitrary::Unstructured;
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    let [_, day, artifact] = &args[..] else {
+        panic!("usage: render <day> <artifact>")
+    };
+
+    let (generate, _) = rust_fuzz::day(day).unwrap();
+    let data = fs::read(artifact).unwrap();
+
+    println!("{}", generate(&mut Unstructured::new(&data)).unwrap());
+}
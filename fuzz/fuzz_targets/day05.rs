@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_fuzz::{day05, fuzz};
+
+fuzz_target!(|data: &[u8]| fuzz(data, day05::generate, day05::run));
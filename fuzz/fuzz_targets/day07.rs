@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_fuzz::{day07, fuzz};
+
+fuzz_target!(|data: &[u8]| fuzz(data, day07::generate, day07::run));
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_fuzz::{day15, fuzz};
+
+fuzz_target!(|data: &[u8]| fuzz(data, day15::generate, day15::run));
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_fuzz::{day23, fuzz};
+
+fuzz_target!(|data: &[u8]| fuzz(data, day23::generate, day23::run));
@@ -0,0 +1,18 @@
+include!("../../src/bin/01/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    crate::lines(u, 1..=64, |u| {
+        Ok(format!(
+            "{}   {}",
+            u.int_in_range(0..=99999u32)?,
+            u.int_in_range(0..=99999u32)?
+        ))
+    })
+}
+
+pub fn run(input: &str) {
+    let (mut xs, mut ys) = parse(input.lines());
+    let (x_tally, y_tally) = tally(input.as_bytes()).unwrap();
+    assert_eq!(process_part1(&mut xs, &mut ys), distance(&x_tally, &y_tally));
+    assert_eq!(process_part2(&xs, &ys), similarity(&x_tally, &y_tally));
+}
@@ -0,0 +1,19 @@
+include!("../../src/bin/02/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    crate::lines(u, 1..=32, |u| {
+        let levels = (0..crate::size(u, 1..=8)?)
+            .map(|_| u.int_in_range(1..=99u32).map(|v| v.to_string()))
+            .collect::<arbitrary::Result<Vec<_>>>()?;
+        Ok(levels.join(" "))
+    })
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    process_part1(&ns);
+    process_part2(&ns);
+    for report in &ns {
+        diagnose(report, report.len());
+    }
+}
@@ -0,0 +1,17 @@
+include!("../../src/bin/03/main.rs");
+
+const FRAGMENTS: [&str; 12] = [
+    "mul(", ")", ",", "do()", "don't()", "do(", "don't", "1", "23", "456", "x", "\n",
+];
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    (0..crate::size(u, 0..=128)?)
+        .map(|_| crate::choose(u, &FRAGMENTS))
+        .collect()
+}
+
+pub fn run(input: &str) {
+    let instructions = parse(input.lines());
+    process_part1(&instructions);
+    process_part2(&instructions);
+}
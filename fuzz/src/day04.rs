@@ -0,0 +1,16 @@
+include!("../../src/bin/04/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let grid = crate::grid(u, 1..=20, 1..=20, &['X', 'M', 'A', 'S'])?;
+    Ok(crate::render(&grid))
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    process_part1(&ns);
+    process_part2(&ns);
+    find_words(&ns, &["XMAS", "SAMX", "A"]);
+    if let Ok(template) = Template::parse(input) {
+        find_templates(&ns, &template, true, true);
+    }
+}
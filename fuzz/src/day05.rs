@@ -0,0 +1,56 @@
+include!("../../src/bin/05/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut pages = (10..100).collect::<Vec<u32>>();
+    let count = crate::size(u, 1..=16)?;
+    for i in 0..count {
+        let j = u.int_in_range(i..=pages.len() - 1)?;
+        pages.swap(i, j);
+    }
+    pages.truncate(count);
+
+    // dropping rules leaves unrelated pages, so some updates have no unique order
+    let partial = u.arbitrary::<bool>()?;
+    let mut rules = vec![];
+    for (i, a) in pages.iter().enumerate() {
+        for b in &pages[i + 1..] {
+            if !partial || u.arbitrary::<bool>()? {
+                rules.push(format!("{}|{}", a, b));
+            }
+        }
+    }
+    let rules = rules.join("\n");
+
+    let updates = crate::lines(u, 1..=8, |u| {
+        let mut update = pages.clone();
+        for i in 0..update.len() {
+            let j = u.int_in_range(i..=update.len() - 1)?;
+            update.swap(i, j);
+        }
+        update.truncate(crate::size(u, 1..=pages.len())?);
+        Ok(update
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(","))
+    })?;
+
+    Ok(format!("{}\n\n{}", rules, updates))
+}
+
+pub fn run(input: &str) {
+    let (rules, updates) = parse(input);
+    let sorted = process(&rules, &updates);
+    for update in &updates {
+        let explanation = explain(&rules, update);
+        if let Some(moves) = explanation.moves {
+            assert!(moves <= explanation.violations.len());
+            assert_eq!(moves == 0, explanation.violations.is_empty());
+        } else {
+            assert!(explanation.corrected.is_err());
+            if !explanation.violations.is_empty() {
+                assert!(sorted.is_err());
+            }
+        }
+    }
+}
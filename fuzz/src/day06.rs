@@ -0,0 +1,18 @@
+include!("../../src/bin/06/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut grid = crate::grid(u, 1..=16, 1..=16, &['.', '.', '.', '#'])?;
+    let glyph = crate::choose(u, &['^', '>', 'v', '<'])?;
+    crate::place(u, &mut grid, glyph, 0)?;
+    Ok(crate::render(&grid))
+}
+
+pub fn run(input: &str) {
+    let (guard, map) = parse(input);
+    match map.patrol(guard) {
+        Patrol::Exit { path, exit } => assert_eq!(path.last().unwrap().position, exit),
+        Patrol::Loop { path, cycle } => assert!(path.ends_with(&cycle)),
+    }
+    process_part1(guard, &map);
+    process_part2(guard, &map);
+}
@@ -0,0 +1,63 @@
+include!("../../src/bin/07/main.rs");
+
+const OPERATORS: [Operator<u64>; 3] = [Operator::ADD, Operator::MUL, Operator::CONCAT];
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    crate::lines(u, 1..=16, |u| {
+        let values = (0..crate::size(u, 1..=8)?)
+            .map(|_| u.int_in_range(0..=999u64))
+            .collect::<arbitrary::Result<Vec<_>>>()?;
+        // usually aim at a value some choice of operators actually reaches
+        let ops = (1..values.len())
+            .map(|_| u.choose(&OPERATORS))
+            .collect::<arbitrary::Result<Vec<_>>>()?;
+        let test_value = match evaluate(&values, &ops) {
+            Some(v) if u.ratio(3, 4)? => v,
+            _ => u.int_in_range(0..=u64::MAX)?,
+        };
+        let values = values.iter().map(u64::to_string).collect::<Vec<_>>();
+        Ok(format!("{}: {}", test_value, values.join(" ")))
+    })
+}
+
+// tries every sequence of operators forwards
+fn reference(test_value: u64, values: &[u64], operators: &[Operator<u64>]) -> usize {
+    let slots = values.len() as u32 - 1;
+    (0..operators.len().pow(slots))
+        .filter(|code| {
+            let ops = (0..slots)
+                .map(|i| &operators[code / operators.len().pow(i) % operators.len()])
+                .collect::<Vec<_>>();
+            evaluate(values, &ops) == Some(test_value)
+        })
+        .count()
+}
+
+pub fn run(input: &str) {
+    let ns = parse::<u64>(input.lines());
+
+    for operators in [&OPERATORS[..2], &OPERATORS[..]] {
+        let mut total = Some(0u64);
+        for (test_value, values) in &ns {
+            let count = solutions(test_value, values, operators).count();
+            assert_eq!(count, reference(*test_value, values, operators));
+            if count > 0 {
+                total = total.and_then(|t| t.checked_add(*test_value));
+            }
+        }
+        assert_eq!(process(&ns, operators), total);
+    }
+    process_part1(&ns);
+    process_part2(&ns);
+
+    let wide_operators = [Operator::ADD, Operator::MUL, Operator::CONCAT];
+    for (test_value, values) in &ns {
+        let wide = values.iter().map(|&v| v as u128).collect::<Vec<_>>();
+        assert_eq!(
+            solutions(test_value, values, &OPERATORS).take(16).count(),
+            solutions(&(*test_value as u128), &wide, &wide_operators)
+                .take(16)
+                .count(),
+        );
+    }
+}
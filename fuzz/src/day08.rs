@@ -0,0 +1,17 @@
+include!("../../src/bin/08/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let grid = crate::grid(u, 1..=20, 1..=20, &['.', '.', '.', '.', 'a', 'A', '0'])?;
+    Ok(crate::render(&grid))
+}
+
+pub fn run(input: &str) {
+    let (bounds, antennae) = parse(input);
+    process_part1(bounds, &antennae);
+    process_part2(bounds, &antennae);
+    assert_eq!(
+        process(bounds, &antennae, Resonance::Double),
+        process(bounds, &antennae, Resonance::Harmonics(1))
+    );
+    assert_eq!(render(bounds, &antennae, Resonance::Harmonics(3)).len(), input.len());
+}
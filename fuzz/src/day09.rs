@@ -0,0 +1,89 @@
+include!("../../src/bin/09/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    (0..crate::size(u, 1..=200)?)
+        .map(|i| {
+            let min = if i % 2 == 0 { 1 } else { 0 };
+            u.int_in_range(min..=9u32)
+                .map(|d| char::from_digit(d, 10).unwrap())
+        })
+        .collect()
+}
+
+// block-by-block reference for both parts
+fn expand(disk: &DiskMap) -> Vec<Option<usize>> {
+    let end = disk
+        .files
+        .iter()
+        .chain(&disk.gaps)
+        .map(|&(s, l)| s + l)
+        .max()
+        .unwrap_or(0);
+    let mut blocks = vec![None; end];
+    for (id, &(start, length)) in disk.files.iter().enumerate() {
+        blocks[start..start + length].fill(Some(id));
+    }
+    blocks
+}
+
+fn reference_checksum(blocks: &[Option<usize>]) -> u128 {
+    blocks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| b.map_or(0, |id| (i * id) as u128))
+        .sum()
+}
+
+fn reference_part1(mut blocks: Vec<Option<usize>>) -> u128 {
+    let (mut i, mut j) = (0, blocks.len());
+    while i < j {
+        if blocks[i].is_some() {
+            i += 1;
+        } else if blocks[j - 1].is_none() {
+            j -= 1;
+        } else {
+            blocks.swap(i, j - 1);
+        }
+    }
+    reference_checksum(&blocks)
+}
+
+fn reference_part2(mut blocks: Vec<Option<usize>>, files: &[Span]) -> u128 {
+    for (id, &(start, length)) in files.iter().enumerate().rev() {
+        let target = (0..start).find(|&t| blocks[t..t + length].iter().all(Option::is_none));
+        if let Some(t) = target {
+            blocks[start..start + length].fill(None);
+            blocks[t..t + length].fill(Some(id));
+        }
+    }
+    reference_checksum(&blocks)
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input);
+    let blocks = expand(&ns);
+    assert_eq!(process_part1(&ns), reference_part1(blocks.clone()));
+    assert_eq!(
+        process_part2(&ns),
+        reference_part2(blocks.clone(), &ns.files)
+    );
+
+    let strategies: [&mut dyn Strategy; 5] = [
+        &mut BlockCompaction,
+        &mut FirstFit,
+        &mut BestFit,
+        &mut WorstFit,
+        &mut NextFit,
+    ];
+    for strategy in strategies {
+        let report = simulate(&ns, strategy);
+        let used = report.layout.iter().map(|&(_, (_, l))| l).sum::<usize>();
+        assert_eq!(used, blocks.iter().flatten().count());
+        assert_eq!(
+            report.render().chars().filter(|&c| c != '.').count(),
+            used,
+            "{} overlaps",
+            report.strategy
+        );
+    }
+}
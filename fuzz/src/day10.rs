@@ -0,0 +1,22 @@
+include!("../../src/bin/10/main.rs");
+
+const HEIGHTS: [char; 11] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '.'];
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let grid = crate::grid(u, 1..=12, 1..=12, &HEIGHTS)?;
+    Ok(crate::render(&grid))
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    process_part1(&ns);
+    process_part2(&ns);
+
+    let analysis = analyse(&ns);
+    for (row, col) in starts(&ns) {
+        let found = trails((row, col), &ns, &analysis);
+        assert_eq!(found.len(), analysis.trails[row][col]);
+        let ends = found.iter().map(|t| *t.last().unwrap()).collect::<HashSet<_>>();
+        assert_eq!(ends, analysis.summits[row][col]);
+    }
+}
@@ -0,0 +1,17 @@
+include!("../../src/bin/11/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let stones = (0..crate::size(u, 1..=8)?)
+        .map(|_| u.int_in_range(0..=1_000_000u64).map(|v| v.to_string()))
+        .collect::<arbitrary::Result<Vec<_>>>()?;
+    Ok(stones.join(" "))
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input);
+    let stats = stats(&ns, 75, &RULES);
+    assert_eq!(stats[75].total, process(&ns, 75, &RULES));
+    if let Some(count) = process_matrix(&ns, 25, &RULES, 100) {
+        assert_eq!(count, stats[25].total);
+    }
+}
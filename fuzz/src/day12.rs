@@ -0,0 +1,112 @@
+include!("../../src/bin/12/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let grid = crate::grid(u, 1..=20, 1..=20, &['A', 'B', 'C', 'D'])?;
+    Ok(crate::render(&grid))
+}
+
+// breadth-first reference: (plant, area, perimeter, sides) in reading order
+fn reference(ns: &Vec<Vec<char>>) -> Vec<(char, usize, usize, usize)> {
+    let mut seen = vec![vec![false; ns[0].len()]; ns.len()];
+    let mut found = vec![];
+    for (row, col) in (0..ns.len()).flat_map(|r| (0..ns[0].len()).map(move |c| (r, c))) {
+        if seen[row][col] {
+            continue;
+        }
+        seen[row][col] = true;
+        let mut queue = std::collections::VecDeque::from([(row, col)]);
+        let mut cells = std::collections::HashSet::new();
+        while let Some(cell) = queue.pop_front() {
+            cells.insert(cell);
+            for direction in DIRECTIONS {
+                if !fenced(ns, cell, direction) {
+                    let next = (
+                        cell.0.wrapping_add_signed(direction.0),
+                        cell.1.wrapping_add_signed(direction.1),
+                    );
+                    if !seen[next.0][next.1] {
+                        seen[next.0][next.1] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let perimeter = cells
+            .iter()
+            .map(|&cell| DIRECTIONS.iter().filter(|&&d| fenced(ns, cell, d)).count())
+            .sum();
+        // a side is a maximal run of fences facing the same way along a row or column
+        let mut sides = 0;
+        for (i, direction) in DIRECTIONS.iter().enumerate() {
+            let along = DIRECTIONS[(i + 1) % 4];
+            for &cell in &cells {
+                let previous = (
+                    cell.0.wrapping_add_signed(-along.0),
+                    cell.1.wrapping_add_signed(-along.1),
+                );
+                let starts = fenced(ns, cell, *direction)
+                    && !(cells.contains(&previous) && fenced(ns, previous, *direction));
+                sides += starts as usize;
+            }
+        }
+        found.push((ns[row][col], cells.len(), perimeter, sides));
+    }
+    found
+}
+
+// whether an 8-connected walk that avoids the given region reaches the map edge
+fn escapes(ns: &Vec<Vec<char>>, regions: &[Region], start: Cell, avoid: usize) -> bool {
+    let (height, width) = (ns.len(), ns[0].len());
+    let mut blocked = vec![vec![false; width]; height];
+    for &(row, col) in &regions[avoid].cells {
+        blocked[row][col] = true;
+    }
+    let mut stack = vec![start];
+    blocked[start.0][start.1] = true;
+    while let Some((row, col)) = stack.pop() {
+        if row == 0 || col == 0 || row + 1 == height || col + 1 == width {
+            return true;
+        }
+        for (r, c) in (row - 1..=row + 1).flat_map(|r| (col - 1..=col + 1).map(move |c| (r, c))) {
+            if !blocked[r][c] {
+                blocked[r][c] = true;
+                stack.push((r, c));
+            }
+        }
+    }
+    false
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    process_part1(&ns);
+    process_part2(&ns);
+
+    let regions = regions(&ns);
+    assert_eq!(
+        regions
+            .iter()
+            .map(|r| (r.plant, r.area, r.perimeter, r.sides))
+            .collect::<Vec<_>>(),
+        reference(&ns)
+    );
+    for r in &regions {
+        assert_eq!(r.cells.len(), r.area);
+        assert!(r.cells.iter().all(|&(row, col)| {
+            (r.bounds.0 .0..=r.bounds.1 .0).contains(&row)
+                && (r.bounds.0 .1..=r.bounds.1 .1).contains(&col)
+        }));
+    }
+
+    for (i, (r, g)) in regions.iter().zip(geometries(&ns, &regions)).enumerate() {
+        assert_eq!(g.sides(), r.sides);
+        let area = signed_area(&g.outer) + g.holes.iter().map(|h| signed_area(h)).sum::<i64>();
+        assert_eq!(area, 2 * r.area as i64);
+        assert!(g.holes.iter().all(|h| signed_area(h) < 0));
+        assert_ne!(g.enclosed_by, Some(i));
+        if let Some(e) = g.enclosed_by {
+            assert!(!escapes(&ns, &regions, r.cells[0], e));
+        }
+    }
+}
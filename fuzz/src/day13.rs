@@ -0,0 +1,68 @@
+include!("../../src/bin/13/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let machines = (0..crate::size(u, 1..=8)?)
+        .map(|_| {
+            let (a, b, prize) = if u.arbitrary()? {
+                // collinear buttons, with the prize usually on their line
+                let (dx, dy) = (u.int_in_range(1..=20usize)?, u.int_in_range(1..=20usize)?);
+                let [ka, kb, kp] = [(); 3].map(|_| u.int_in_range(1..=99usize));
+                let (ka, kb, kp) = (ka?, kb?, kp?);
+                let prize = if u.arbitrary()? {
+                    (kp * dx, kp * dy)
+                } else {
+                    (
+                        u.int_in_range(0..=2000usize)?,
+                        u.int_in_range(0..=2000usize)?,
+                    )
+                };
+                ((ka * dx, ka * dy), (kb * dx, kb * dy), prize)
+            } else {
+                let [ax, ay, bx, by] = [(); 4].map(|_| u.int_in_range(1..=99usize));
+                let prize = (
+                    u.int_in_range(0..=20000usize)?,
+                    u.int_in_range(0..=20000usize)?,
+                );
+                ((ax?, ay?), (bx?, by?), prize)
+            };
+            Ok(format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}",
+                a.0, a.1, b.0, b.1, prize.0, prize.1
+            ))
+        })
+        .collect::<arbitrary::Result<Vec<_>>>()?;
+    Ok(machines.join("\n\n"))
+}
+
+// tries every count of A presses
+fn reference(machine: &Machine, config: &Config) -> Option<i128> {
+    let Machine { a, b, prize } = *machine;
+    let limit = config.limit.unwrap_or(i128::MAX);
+    (0..=(prize.0 / a.0).min(limit))
+        .filter_map(|n| {
+            let (x, y) = (prize.0 - n * a.0, prize.1 - n * a.1);
+            (y >= 0 && x % b.0 == 0 && y % b.1 == 0 && x / b.0 == y / b.1 && x / b.0 <= limit)
+                .then(|| n * config.costs.0 + x / b.0 * config.costs.1)
+        })
+        .min()
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input);
+    let uncapped = Config { offset: 0, ..PART2 };
+    for config in [PART1, uncapped] {
+        for machine in &ns {
+            let presses = solve(machine, &config);
+            assert_eq!(
+                presses.as_ref().map(|p| p.cost),
+                reference(machine, &config)
+            );
+            if let Some(p) = presses {
+                assert_eq!(p.a * machine.a.0 + p.b * machine.b.0, machine.prize.0);
+                assert_eq!(p.a * machine.a.1 + p.b * machine.b.1, machine.prize.1);
+            }
+        }
+    }
+    process(&ns, &PART1);
+    process(&ns, &PART2);
+}
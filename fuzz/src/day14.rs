@@ -0,0 +1,58 @@
+include!("../../src/bin/14/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    crate::lines(u, 1..=32, |u| {
+        Ok(format!(
+            "p={},{} v={},{}",
+            u.int_in_range(0..=10)?,
+            u.int_in_range(0..=6)?,
+            u.int_in_range(-100..=100)?,
+            u.int_in_range(-100..=100)?
+        ))
+    })
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    let size = Point(11, 7);
+    process_part1(&ns, size);
+
+    // step one second at a time as a reference for the closed form
+    let mut stepped = ns.iter().map(|r| r.position).collect::<Vec<_>>();
+    for t in 0..=100 {
+        assert_eq!(positions(&ns, t, size), stepped);
+        for (p, r) in stepped.iter_mut().zip(&ns) {
+            *p = Point(
+                (p.0 + r.velocity.0).rem_euclid(size.0),
+                (p.1 + r.velocity.1).rem_euclid(size.1),
+            );
+        }
+    }
+
+    let t = i64::MAX;
+    assert_eq!(positions(&ns, t, size), positions(&ns, t % 77, size));
+    let quadrants = quadrants(&ns, t, size);
+    assert_eq!(count_in(&ns, t, size, 0..11, 0..7), ns.len());
+    assert!(quadrants.iter().sum::<usize>() <= ns.len());
+    assert_eq!(safety_factor(&ns, t, size), quadrants.iter().product());
+
+    for strategy in [Strategy::Variance, Strategy::Entropy] {
+        let detector = Detector {
+            strategy,
+            threshold: None,
+        };
+        let frame = process_part2(&ns, size, &detector).unwrap();
+        assert!((0..77).contains(&frame.t));
+        assert_eq!(frame.occupied, Frame::new(&ns, frame.t + 77, size).occupied);
+        let occupied = frame.occupied.iter().flatten().filter(|&&o| o).count();
+        assert_eq!(
+            occupied,
+            positions(&ns, frame.t, size)
+                .iter()
+                .map(|p| (p.0, p.1))
+                .unique()
+                .count()
+        );
+    }
+    process_part2(&ns, size, &DETECTOR);
+}
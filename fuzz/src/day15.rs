@@ -0,0 +1,195 @@
+include!("../../src/bin/15/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut grid = crate::grid(u, 1..=10, 1..=10, &['.', '.', 'O', '#'])?;
+    if crate::size(u, 0..=3)? > 0 {
+        crate::border(&mut grid, '#');
+    }
+    crate::place(u, &mut grid, '@', 0)?;
+    let moves = crate::lines(u, 1..=4, |u| {
+        (0..crate::size(u, 1..=40)?)
+            .map(|_| crate::choose(u, &['^', 'v', '<', '>']))
+            .collect()
+    })?;
+    Ok(format!("{}\n\n{}", crate::render(&grid), moves))
+}
+
+// the original per-part implementations, kept as references for scales 1 and 2
+mod reference {
+    use super::{Cell, Direction, Point};
+    use itertools::Itertools;
+
+    fn step(point: Point, direction: Direction) -> Point {
+        match direction {
+            Direction::Up => Point(point.0.wrapping_sub(1), point.1),
+            Direction::Down => Point(point.0 + 1, point.1),
+            Direction::Left => Point(point.0, point.1.wrapping_sub(1)),
+            Direction::Right => Point(point.0, point.1 + 1),
+        }
+    }
+
+    #[derive(PartialEq, Clone, Copy)]
+    enum Cell2 {
+        None,
+        BoxLeft,
+        BoxRight,
+        Wall,
+    }
+
+    fn cell_at(map: &[Vec<Cell2>], point: &Point) -> Cell2 {
+        map.get(point.0)
+            .and_then(|row| row.get(point.1))
+            .copied()
+            .unwrap_or(Cell2::Wall)
+    }
+
+    fn push(map: &mut Vec<Vec<Cell2>>, from: &Point, direction: &Direction, tail: bool) -> bool {
+        let nextpoint = step(*from, *direction);
+        let nextcell = cell_at(map, &nextpoint);
+        let vertical = *direction == Direction::Up || *direction == Direction::Down;
+        let result = match nextcell {
+            Cell2::None => true,
+            Cell2::Wall => false,
+            Cell2::BoxLeft if vertical => {
+                push(map, &nextpoint, direction, false)
+                    && (tail || push(map, &step(*from, Direction::Right), direction, true))
+            }
+            Cell2::BoxRight if vertical => {
+                push(map, &nextpoint, direction, false)
+                    && (tail || push(map, &step(*from, Direction::Left), direction, true))
+            }
+            _ => push(map, &nextpoint, direction, false),
+        };
+
+        if result && (nextcell == Cell2::BoxLeft || nextcell == Cell2::BoxRight) {
+            map[nextpoint.0][nextpoint.1] = Cell2::None;
+            let pushed_into = step(nextpoint, *direction);
+            map[pushed_into.0][pushed_into.1] = nextcell;
+        }
+
+        result
+    }
+
+    pub fn part1(map: &[Vec<Cell>], position: &Point, moves: &[Direction]) -> usize {
+        let mut map = map.to_vec();
+        let mut position = *position;
+        for &direction in moves {
+            let Point(r, c) = position;
+            let slice = match direction {
+                Direction::Up => (0..r).rev().map(|rr| map[rr][c]).collect::<Vec<_>>(),
+                Direction::Down => (r + 1..map.len()).map(|rr| map[rr][c]).collect(),
+                Direction::Left => (0..c).rev().map(|cc| map[r][cc]).collect(),
+                Direction::Right => (c + 1..map[0].len()).map(|cc| map[r][cc]).collect(),
+            };
+            let chunks = slice.iter().chunk_by(|v| **v);
+            let mut chunk_iter = chunks.into_iter();
+            let Some((first, boxes)) = chunk_iter.next() else {
+                continue;
+            };
+            match (first, chunk_iter.next().map(|(c, _)| c)) {
+                (Cell::None, _) => position = step(position, direction),
+                (Cell::Box, Some(Cell::None)) => {
+                    position = step(position, direction);
+                    let end = boxes.fold(position, |p, _| step(p, direction));
+                    map[position.0][position.1] = Cell::None;
+                    map[end.0][end.1] = Cell::Box;
+                }
+                _ => {}
+            }
+        }
+        map.iter()
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.iter()
+                    .positions(|&c| c == Cell::Box)
+                    .map(move |c| r * 100 + c)
+            })
+            .sum()
+    }
+
+    pub fn part2(map: &[Vec<Cell>], &Point(row, col): &Point, moves: &[Direction]) -> usize {
+        let mut map = map
+            .iter()
+            .map(|r| {
+                r.iter()
+                    .flat_map(|c| match c {
+                        Cell::None => [Cell2::None; 2],
+                        Cell::Box => [Cell2::BoxLeft, Cell2::BoxRight],
+                        Cell::Wall => [Cell2::Wall; 2],
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        let mut position = Point(row, col * 2);
+        for direction in moves {
+            let mut next = map.clone();
+            if push(&mut next, &position, direction, false) {
+                map = next;
+                position = step(position, *direction);
+            }
+        }
+        map.iter()
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.iter()
+                    .positions(|&c| c == Cell2::BoxLeft)
+                    .map(move |c| r * 100 + c)
+            })
+            .sum()
+    }
+}
+
+pub fn run(input: &str) {
+    let (map, position, moves) = parse(input.lines());
+    assert_eq!(
+        process(&map, &position, &moves, 1),
+        reference::part1(&map, &position, &moves)
+    );
+    assert_eq!(
+        process(&map, &position, &moves, 2),
+        reference::part2(&map, &position, &moves)
+    );
+
+    let mut warehouse = Warehouse::new(&map, &position, 3);
+    for &direction in &moves {
+        let before = warehouse.boxes.clone();
+        let moved = warehouse.step(direction);
+        if moved.is_none() {
+            assert_eq!(warehouse.boxes, before);
+        }
+        // no two boxes may overlap, and none may sit on a wall
+        let mut cells = HashSet::new();
+        for &b in &warehouse.boxes {
+            for p in warehouse.cells(b) {
+                assert!(!warehouse.wall(Some(p)));
+                assert!(cells.insert(p.0 * 1000 + p.1));
+            }
+        }
+        assert!(!warehouse.wall(Some(warehouse.robot)));
+        assert!(!cells.contains(&(warehouse.robot.0 * 1000 + warehouse.robot.1)));
+    }
+
+    for scale in 1..=3 {
+        let mut replay = Replay::new(Warehouse::new(&map, &position, scale), &moves);
+        let mut renders = vec![replay.warehouse.render()];
+        while replay.forward().is_some() {
+            let rendered = replay.warehouse.render();
+            let parsed = Warehouse::parse(&rendered);
+            assert_eq!(parsed.render(), rendered);
+            assert_eq!(parsed.gps(), replay.warehouse.gps());
+            renders.push(rendered);
+        }
+        assert_eq!(
+            replay.warehouse.gps(),
+            process(&map, &position, &moves, scale)
+        );
+
+        // undoing every move retraces the same states in reverse
+        while let Some(step) = replay.back() {
+            assert!(step.success || step.boxes.is_empty());
+            assert_eq!(replay.warehouse.render(), renders[replay.log.len()]);
+        }
+        replay.seek(moves.len() / 2);
+        assert_eq!(replay.warehouse.render(), renders[moves.len() / 2]);
+    }
+}
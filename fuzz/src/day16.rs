@@ -0,0 +1,19 @@
+include!("../../src/bin/16/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut grid = crate::grid(u, 3..=10, 3..=10, &['.', '.', '#'])?;
+    crate::border(&mut grid, '#');
+    let start = (grid.len() - 2, 1);
+    grid[start.0][start.1] = 'S';
+    let end = crate::place(u, &mut grid, 'E', 1)?;
+    if start == end || !crate::connected(&grid, start, end, '#') {
+        return Err(arbitrary::Error::IncorrectFormat);
+    }
+    Ok(crate::render(&grid))
+}
+
+pub fn run(input: &str) {
+    let (map, start) = parse(input.lines());
+    process_part1(&map, &start);
+    process_part2(&map, &start);
+}
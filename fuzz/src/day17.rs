@@ -0,0 +1,28 @@
+include!("../../src/bin/17/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut program = vec![(0, 3)];
+    for _ in 0..crate::size(u, 0..=6)? {
+        let opcode = crate::choose(u, &[1, 2, 4, 5, 6, 7])?;
+        program.push((opcode, u.int_in_range(0..=6)?));
+    }
+    program.push((3, 0));
+
+    Ok(format!(
+        "Register A: {}\nRegister B: {}\nRegister C: {}\n\nProgram: {}",
+        u.int_in_range(0..=1u64 << 40)?,
+        u.int_in_range(0..=7)?,
+        u.int_in_range(0..=7)?,
+        program
+            .iter()
+            .map(|(opcode, operand)| format!("{},{}", opcode, operand))
+            .collect::<Vec<_>>()
+            .join(",")
+    ))
+}
+
+// Part 2 relies on the puzzle's program printing one octal digit of A per loop.
+pub fn run(input: &str) {
+    let (state, program) = parse(input.lines());
+    process_part1(state, &program);
+}
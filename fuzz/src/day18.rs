@@ -0,0 +1,24 @@
+include!("../../src/bin/18/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let input = crate::lines(u, 2..=48, |u| {
+        Ok(format!(
+            "{},{}",
+            u.int_in_range(0..=6)?,
+            u.int_in_range(0..=6)?
+        ))
+    })?;
+
+    let ns = parse(input.lines());
+    if process_part1(&ns, 7).is_some() {
+        return Err(arbitrary::Error::IncorrectFormat);
+    }
+
+    Ok(input)
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    process_part1(&ns, 7);
+    process_part2(&ns, 7);
+}
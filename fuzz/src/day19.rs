@@ -0,0 +1,28 @@
+include!("../../src/bin/19/main.rs");
+
+const COLORS: [char; 5] = ['w', 'u', 'b', 'r', 'g'];
+
+fn stripes(
+    u: &mut arbitrary::Unstructured,
+    length: std::ops::RangeInclusive<usize>,
+) -> arbitrary::Result<String> {
+    (0..crate::size(u, length)?)
+        .map(|_| crate::choose(u, &COLORS))
+        .collect()
+}
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut towels = (0..crate::size(u, 1..=8)?)
+        .map(|_| stripes(u, 1..=3))
+        .collect::<arbitrary::Result<Vec<_>>>()?;
+    towels.sort();
+    towels.dedup();
+    let patterns = crate::lines(u, 1..=8, |u| stripes(u, 1..=24))?;
+    Ok(format!("{}\n\n{}", towels.join(", "), patterns))
+}
+
+pub fn run(input: &str) {
+    let (towels, patterns) = parse(input.lines());
+    process_part1(&towels, &patterns);
+    process_part2(&towels, &patterns);
+}
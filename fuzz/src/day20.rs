@@ -0,0 +1,40 @@
+include!("../../src/bin/20/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let (rows, cols) = (crate::size(u, 3..=15)?, crate::size(u, 3..=15)?);
+    let mut grid = vec![vec!['#'; cols]; rows];
+    let start = crate::place(u, &mut grid, 'S', 1)?;
+
+    let mut current = start;
+    for _ in 0..crate::size(u, 1..=rows * cols)? {
+        let (r, c) = current;
+        let candidates = [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)]
+            .into_iter()
+            .filter(|&(nr, nc)| nr > 0 && nr < rows - 1 && nc > 0 && nc < cols - 1)
+            .filter(|&(nr, nc)| grid[nr][nc] == '#')
+            .filter(|&(nr, nc)| {
+                [(nr - 1, nc), (nr + 1, nc), (nr, nc - 1), (nr, nc + 1)]
+                    .iter()
+                    .all(|&p| p == current || grid[p.0][p.1] == '#')
+            })
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            break;
+        }
+        current = crate::choose(u, &candidates)?;
+        grid[current.0][current.1] = '.';
+    }
+
+    if current == start {
+        return Err(arbitrary::Error::IncorrectFormat);
+    }
+    grid[current.0][current.1] = 'E';
+
+    Ok(crate::render(&grid))
+}
+
+pub fn run(input: &str) {
+    let (map, start, end) = parse(input.lines());
+    process_part1(&map, &start, &end);
+    process_part2(&map, &start, &end);
+}
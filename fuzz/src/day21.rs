@@ -0,0 +1,39 @@
+include!("../../src/bin/21/main.rs");
+
+const DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+// keys the numeric pad doesn't have
+const STRAY: [char; 6] = ['+', 'B', ' ', '^', '<', 'é'];
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    crate::lines(u, 1..=5, |u| {
+        let mut code = (0..crate::size(u, 1..=4)?)
+            .map(|_| {
+                if u.ratio(1, 10)? {
+                    crate::choose(u, &STRAY)
+                } else {
+                    crate::choose(u, &DIGITS)
+                }
+            })
+            .collect::<arbitrary::Result<String>>()?;
+        if u.ratio(9, 10)? {
+            code.push('A');
+        }
+        Ok(code)
+    })
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    // codes that can't be typed must add nothing rather than crash
+    let typeable = ns
+        .iter()
+        .filter(|n| {
+            n.strip_suffix('A')
+                .is_some_and(|d| !d.is_empty() && d.chars().all(|c| c.is_ascii_digit()))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(process_part1(&ns), process_part1(&typeable));
+    assert_eq!(process_part2(&ns), process_part2(&typeable));
+}
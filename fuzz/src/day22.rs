@@ -0,0 +1,14 @@
+include!("../../src/bin/22/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    crate::lines(u, 1..=16, |u| {
+        u.int_in_range(0..=(1usize << 24) - 1)
+            .map(|v| v.to_string())
+    })
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    process_part1(&ns);
+    process_part2(&ns);
+}
@@ -0,0 +1,30 @@
+include!("../../src/bin/23/main.rs");
+
+const NAMES: [&str; 16] = [
+    "ta", "tb", "tc", "td", "aa", "ab", "ac", "ad", "ba", "bb", "bc", "bd", "ca", "cb", "cc", "cd",
+];
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut connections = std::collections::HashSet::new();
+    for _ in 0..crate::size(u, 1..=48)? {
+        let (a, b) = (crate::choose(u, &NAMES)?, crate::choose(u, &NAMES)?);
+        if a != b && !connections.contains(&(b, a)) {
+            connections.insert((a, b));
+        }
+    }
+    if connections.is_empty() {
+        return Err(arbitrary::Error::IncorrectFormat);
+    }
+
+    Ok(connections
+        .iter()
+        .map(|(a, b)| format!("{}-{}", a, b))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+pub fn run(input: &str) {
+    let ns = parse(input.lines());
+    process_part1(&ns);
+    process_part2(&ns);
+}
@@ -0,0 +1,40 @@
+include!("../../src/bin/24/main.rs");
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let bits = crate::size(u, 1..=8)?;
+    let mut wires = vec![];
+    let mut values = vec![];
+    for prefix in ['x', 'y'] {
+        for i in 0..bits {
+            let wire = format!("{}{:02}", prefix, i);
+            values.push(format!("{}: {}", wire, u.int_in_range(0..=1)?));
+            wires.push(wire);
+        }
+    }
+
+    let outputs = crate::size(u, 1..=bits + 1)?;
+    let mut gates = vec![];
+    for i in 0..crate::size(u, outputs..=outputs + 24)? {
+        let a = u.choose(&wires)?.clone();
+        let b = u.choose(&wires)?.clone();
+        let op = crate::choose(u, &["AND", "OR", "XOR"])?;
+        let wire = if i < outputs {
+            format!("z{:02}", i)
+        } else {
+            format!("g{:02}", i)
+        };
+        gates.push(format!("{} {} {} -> {}", a, op, b, wire));
+        if i >= outputs {
+            wires.push(wire);
+        }
+    }
+    gates.reverse();
+
+    Ok(format!("{}\n\n{}", values.join("\n"), gates.join("\n")))
+}
+
+// Part 2 swaps wire names specific to the real puzzle input.
+pub fn run(input: &str) {
+    let (values, rules) = parse(input.lines());
+    process_part1(&values, &rules);
+}
@@ -0,0 +1,41 @@
+include!("../../src/bin/25/main.rs");
+
+fn schematic(u: &mut arbitrary::Unstructured, lock: bool) -> arbitrary::Result<String> {
+    let heights = [(); 5].map(|_| u.int_in_range(0..=5usize));
+    let heights = heights.into_iter().collect::<arbitrary::Result<Vec<_>>>()?;
+    Ok((0..7)
+        .map(|row| {
+            heights
+                .iter()
+                .map(|&h| {
+                    let filled = if lock { row <= h } else { row >= 6 - h };
+                    if filled {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+pub fn generate(u: &mut arbitrary::Unstructured) -> arbitrary::Result<String> {
+    let mut input = String::new();
+    for i in 0..crate::size(u, 1..=12)? {
+        if i > 0 {
+            input += &"\n".repeat(crate::size(u, 2..=3)?);
+        }
+        let lock = u.arbitrary()?;
+        input += &schematic(u, lock)?;
+    }
+    Ok(input)
+}
+
+pub fn run(input: &str) {
+    let (locks, keys) = parse(input.lines());
+    let rows = input.lines().filter(|line| !line.is_empty()).count();
+    assert_eq!((locks.len() + keys.len()) * 7, rows);
+    process_part1(&locks, &keys);
+}
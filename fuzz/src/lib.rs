@@ -0,0 +1,197 @@
+use std::{collections::VecDeque, ops::RangeInclusive};
+
+use arbitrary::{Error, Result, Unstructured};
+
+// Each day module includes that day's `main.rs` verbatim, so its lints are
+// already reported by the main crate.
+#[allow(warnings)]
+pub mod day01;
+#[allow(warnings)]
+pub mod day02;
+#[allow(warnings)]
+pub mod day03;
+#[allow(warnings)]
+pub mod day04;
+#[allow(warnings)]
+pub mod day05;
+#[allow(warnings)]
+pub mod day06;
+#[allow(warnings)]
+pub mod day07;
+#[allow(warnings)]
+pub mod day08;
+#[allow(warnings)]
+pub mod day09;
+#[allow(warnings)]
+pub mod day10;
+#[allow(warnings)]
+pub mod day11;
+#[allow(warnings)]
+pub mod day12;
+#[allow(warnings)]
+pub mod day13;
+#[allow(warnings)]
+pub mod day14;
+#[allow(warnings)]
+pub mod day15;
+#[allow(warnings)]
+pub mod day16;
+#[allow(warnings)]
+pub mod day17;
+#[allow(warnings)]
+pub mod day18;
+#[allow(warnings)]
+pub mod day19;
+#[allow(warnings)]
+pub mod day20;
+#[allow(warnings)]
+pub mod day21;
+#[allow(warnings)]
+pub mod day22;
+#[allow(warnings)]
+pub mod day23;
+#[allow(warnings)]
+pub mod day24;
+#[allow(warnings)]
+pub mod day25;
+
+pub type Generate = fn(&mut Unstructured) -> Result<String>;
+pub type Run = fn(&str);
+
+pub const DAYS: [(&str, Generate, Run); 25] = [
+    ("day01", day01::generate, day01::run),
+    ("day02", day02::generate, day02::run),
+    ("day03", day03::generate, day03::run),
+    ("day04", day04::generate, day04::run),
+    ("day05", day05::generate, day05::run),
+    ("day06", day06::generate, day06::run),
+    ("day07", day07::generate, day07::run),
+    ("day08", day08::generate, day08::run),
+    ("day09", day09::generate, day09::run),
+    ("day10", day10::generate, day10::run),
+    ("day11", day11::generate, day11::run),
+    ("day12", day12::generate, day12::run),
+    ("day13", day13::generate, day13::run),
+    ("day14", day14::generate, day14::run),
+    ("day15", day15::generate, day15::run),
+    ("day16", day16::generate, day16::run),
+    ("day17", day17::generate, day17::run),
+    ("day18", day18::generate, day18::run),
+    ("day19", day19::generate, day19::run),
+    ("day20", day20::generate, day20::run),
+    ("day21", day21::generate, day21::run),
+    ("day22", day22::generate, day22::run),
+    ("day23", day23::generate, day23::run),
+    ("day24", day24::generate, day24::run),
+    ("day25", day25::generate, day25::run),
+];
+
+pub fn fuzz(data: &[u8], generate: Generate, run: Run) {
+    if let Ok(input) = generate(&mut Unstructured::new(data)) {
+        run(&input);
+    }
+}
+
+pub fn day(name: &str) -> Option<(Generate, Run)> {
+    DAYS.iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|&(_, generate, run)| (generate, run))
+}
+
+pub fn size(u: &mut Unstructured, range: RangeInclusive<usize>) -> Result<usize> {
+    u.int_in_range(range)
+}
+
+pub fn choose<T: Copy>(u: &mut Unstructured, choices: &[T]) -> Result<T> {
+    u.choose(choices).copied()
+}
+
+pub fn lines(
+    u: &mut Unstructured,
+    count: RangeInclusive<usize>,
+    mut line: impl FnMut(&mut Unstructured) -> Result<String>,
+) -> Result<String> {
+    let n = size(u, count)?;
+    Ok((0..n)
+        .map(|_| line(u))
+        .collect::<Result<Vec<_>>>()?
+        .join("\n"))
+}
+
+pub fn grid(
+    u: &mut Unstructured,
+    rows: RangeInclusive<usize>,
+    cols: RangeInclusive<usize>,
+    alphabet: &[char],
+) -> Result<Vec<Vec<char>>> {
+    let (rows, cols) = (size(u, rows)?, size(u, cols)?);
+    (0..rows)
+        .map(|_| (0..cols).map(|_| choose(u, alphabet)).collect())
+        .collect()
+}
+
+pub fn border(grid: &mut [Vec<char>], c: char) {
+    let rows = grid.len();
+    for (r, row) in grid.iter_mut().enumerate() {
+        let cols = row.len();
+        for (col, cell) in row.iter_mut().enumerate() {
+            if r == 0 || r == rows - 1 || col == 0 || col == cols - 1 {
+                *cell = c;
+            }
+        }
+    }
+}
+
+pub fn place(
+    u: &mut Unstructured,
+    grid: &mut [Vec<char>],
+    c: char,
+    margin: usize,
+) -> Result<(usize, usize)> {
+    let rows = grid.len();
+    let cols = grid.first().map_or(0, |row| row.len());
+    if rows <= 2 * margin || cols <= 2 * margin {
+        return Err(Error::IncorrectFormat);
+    }
+    let r = u.int_in_range(margin..=rows - 1 - margin)?;
+    let col = u.int_in_range(margin..=cols - 1 - margin)?;
+    grid[r][col] = c;
+    Ok((r, col))
+}
+
+pub fn connected(grid: &[Vec<char>], from: (usize, usize), to: (usize, usize), wall: char) -> bool {
+    let mut seen = vec![vec![false; grid[0].len()]; grid.len()];
+    let mut queue = VecDeque::from([from]);
+    seen[from.0][from.1] = true;
+
+    while let Some((r, c)) = queue.pop_front() {
+        if (r, c) == to {
+            return true;
+        }
+        for (nr, nc) in [
+            (r.wrapping_sub(1), c),
+            (r + 1, c),
+            (r, c.wrapping_sub(1)),
+            (r, c + 1),
+        ] {
+            if grid
+                .get(nr)
+                .and_then(|row| row.get(nc))
+                .is_some_and(|v| *v != wall)
+                && !seen[nr][nc]
+            {
+                seen[nr][nc] = true;
+                queue.push_back((nr, nc));
+            }
+        }
+    }
+
+    false
+}
+
+pub fn render(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
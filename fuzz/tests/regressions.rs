@@ -0,0 +1,15 @@
+use std::fs;
+
+#[test]
+fn regressions() {
+    let root = concat!(env!("CARGO_MANIFEST_DIR"), "/regressions");
+
+    for day in fs::read_dir(root).unwrap().map(Result::unwrap) {
+        let name = day.file_name().into_string().unwrap();
+        let (_, run) = rust_fuzz::day(&name).unwrap();
+
+        for case in fs::read_dir(day.path()).unwrap().map(Result::unwrap) {
+            run(&fs::read_to_string(case.path()).unwrap());
+        }
+    }
+}
@@ -1,36 +1,79 @@
-use std::{iter::zip, str::Lines};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    iter::zip,
+    str::Lines,
+};
 
-fn parse(iter: Lines) -> (Vec<i32>, Vec<i32>) {
-    let mut xs = vec![];
-    let mut ys = vec![];
+type Tally = BTreeMap<i64, i64>;
 
-    for line in iter {
-        let mut values = line.split_whitespace().map(|s| s.parse::<i32>().unwrap());
-        xs.push(values.next().unwrap());
-        ys.push(values.next().unwrap());
+fn parse_line(line: &str) -> (i64, i64) {
+    let mut values = line.split_whitespace().map(|s| s.parse::<i64>().unwrap());
+    (values.next().unwrap(), values.next().unwrap())
+}
+
+fn parse(iter: Lines) -> (Vec<i64>, Vec<i64>) {
+    iter.map(parse_line).unzip()
+}
+
+fn tally<R: BufRead>(reader: R) -> io::Result<(Tally, Tally)> {
+    let mut xs = Tally::new();
+    let mut ys = Tally::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (x, y) = parse_line(&line);
+        *xs.entry(x).or_default() += 1;
+        *ys.entry(y).or_default() += 1;
     }
 
-    (xs, ys)
+    Ok((xs, ys))
 }
 
-fn clone_sort<T>(xs: &Vec<T>) -> Vec<T>
-where
-    T: Clone,
-    T: Ord,
-{
-    let mut clone = xs.clone();
-    clone.sort();
-    clone
+fn frequencies(ns: &[i64]) -> HashMap<i64, i64> {
+    ns.iter().fold(HashMap::new(), |mut acc, n| {
+        *acc.entry(*n).or_default() += 1;
+        acc
+    })
 }
 
-fn process_part1(xs: &Vec<i32>, ys: &Vec<i32>) -> i32 {
-    zip(clone_sort(xs), clone_sort(ys)).fold(0, |acc, (x, y)| acc + (x - y).abs())
+fn process_part1(xs: &mut [i64], ys: &mut [i64]) -> i64 {
+    xs.sort_unstable();
+    ys.sort_unstable();
+    zip(xs.iter(), ys.iter()).map(|(x, y)| (x - y).abs()).sum()
 }
 
-fn process_part2(xs: &Vec<i32>, ys: &Vec<i32>) -> i32 {
-    xs.iter().fold(0, |acc, x| {
-        acc + x * ys.iter().filter(|y| *y == x).count() as i32
-    })
+fn process_part2(xs: &[i64], ys: &[i64]) -> i64 {
+    let counts = frequencies(ys);
+    xs.iter().map(|x| x * counts.get(x).unwrap_or(&0)).sum()
+}
+
+fn distance(xs: &Tally, ys: &Tally) -> i64 {
+    let mut x_iter = xs.iter().map(|(v, n)| (*v, *n));
+    let mut y_iter = ys.iter().map(|(v, n)| (*v, *n));
+    let (mut x, mut y) = (x_iter.next(), y_iter.next());
+    let mut total = 0;
+
+    while let (Some((xv, xn)), Some((yv, yn))) = (x, y) {
+        let n = xn.min(yn);
+        total += n * (xv - yv).abs();
+        x = if xn > n { Some((xv, xn - n)) } else { x_iter.next() };
+        y = if yn > n { Some((yv, yn - n)) } else { y_iter.next() };
+    }
+
+    total
+}
+
+fn similarity(xs: &Tally, ys: &Tally) -> i64 {
+    xs.iter()
+        .map(|(x, n)| x * n * ys.get(x).unwrap_or(&0))
+        .sum()
 }
 
 #[cfg(test)]
@@ -41,8 +84,8 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let (xs, ys) = parse(INPUT.lines());
-        let result = process_part1(&xs, &ys);
+        let (mut xs, mut ys) = parse(INPUT.lines());
+        let result = process_part1(&mut xs, &mut ys);
         assert_eq!(result, 11);
     }
 
@@ -52,13 +95,33 @@ mod tests {
         let result = process_part2(&xs, &ys);
         assert_eq!(result, 31);
     }
+
+    #[test]
+    fn test_streaming() {
+        let (xs, ys) = tally(INPUT.as_bytes()).unwrap();
+        assert_eq!(distance(&xs, &ys), 11);
+        assert_eq!(similarity(&xs, &ys), 31);
+    }
 }
 
 fn main() {
+    if let Some(path) = env::args().nth(1) {
+        let (xs, ys) = if path == "-" {
+            tally(io::stdin().lock())
+        } else {
+            tally(BufReader::new(File::open(path).unwrap()))
+        }
+        .unwrap();
+
+        println!("{}", distance(&xs, &ys));
+        println!("{}", similarity(&xs, &ys));
+        return;
+    }
+
     let input = include_str!("input.txt");
-    let (xs, ys) = parse(input.lines());
+    let (mut xs, mut ys) = parse(input.lines());
 
-    let result_part1 = process_part1(&xs, &ys);
+    let result_part1 = process_part1(&mut xs, &mut ys);
     println!("{}", result_part1);
 
     let result_part2 = process_part2(&xs, &ys);
@@ -1,4 +1,28 @@
-use std::str::Lines;
+use std::{env, str::Lines};
+
+use itertools::Itertools;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Trend {
+    Increasing,
+    Decreasing,
+}
+
+impl Trend {
+    fn allows(&self, a: i32, b: i32) -> bool {
+        let delta = match self {
+            Trend::Increasing => b - a,
+            Trend::Decreasing => a - b,
+        };
+        (1..=3).contains(&delta)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Diagnosis {
+    trend: Option<Trend>,
+    removals: Vec<usize>,
+}
 
 fn parse(iter: Lines) -> Vec<Vec<i32>> {
     iter.map(|line| {
@@ -9,41 +33,89 @@ fn parse(iter: Lines) -> Vec<Vec<i32>> {
     .collect()
 }
 
-fn skip_index(ns: &Vec<i32>, index: usize) -> Vec<i32> {
-    ns[..index]
-        .iter()
-        .chain(ns[index + 1..].iter())
-        .cloned()
-        .collect()
-}
-
-fn is_safe(report: &Vec<i32>, dampen: bool) -> bool {
-    let mut last_delta: i32 = 0;
-    for (i, (a, b)) in report.iter().zip(report.iter().skip(1)).enumerate() {
-        let delta = a - b;
-
-        let is_good_delta = (last_delta == 0 || delta.signum() == last_delta.signum())
-            && (delta.abs() >= 1 && delta.abs() <= 3);
+fn removals(report: &[i32], trend: Trend, tolerance: usize) -> Option<Vec<usize>> {
+    let n = report.len();
+    if n == 0 {
+        return Some(vec![]);
+    }
 
-        if !is_good_delta {
-            return if dampen {
-                (0..i + 2).any(|i| is_safe(&skip_index(report, i), false))
-            } else {
-                false
-            };
+    // cost[i] is the fewest removals among report[..=i] that keep report[i]
+    let mut cost = vec![None; n];
+    let mut previous = vec![None; n];
+    for i in 0..n {
+        if i <= tolerance {
+            cost[i] = Some(i);
+        }
+        for j in i.saturating_sub(tolerance + 1)..i {
+            if let Some(c) = cost[j] {
+                let candidate = c + i - j - 1;
+                if trend.allows(report[j], report[i])
+                    && candidate <= tolerance
+                    && cost[i].is_none_or(|best| candidate < best)
+                {
+                    cost[i] = Some(candidate);
+                    previous[i] = Some(j);
+                }
+            }
         }
+    }
+
+    let (last, total) = (0..n)
+        .filter_map(|i| cost[i].map(|c| (i, c + n - 1 - i)))
+        .min_by_key(|(_, c)| *c)?;
+    if total > tolerance {
+        return None;
+    }
 
-        last_delta = delta;
+    let mut kept = vec![false; n];
+    let mut current = Some(last);
+    while let Some(i) = current {
+        kept[i] = true;
+        current = previous[i];
     }
-    true
+
+    Some((0..n).filter(|&i| !kept[i]).collect())
+}
+
+fn diagnose(report: &[i32], tolerance: usize) -> Option<Diagnosis> {
+    [Trend::Increasing, Trend::Decreasing]
+        .into_iter()
+        .filter_map(|trend| removals(report, trend, tolerance).map(|r| (trend, r)))
+        .min_by_key(|(_, r)| r.len())
+        .map(|(trend, removals)| Diagnosis {
+            trend: (report.len() - removals.len() >= 2).then_some(trend),
+            removals,
+        })
+}
+
+fn process(reports: &Vec<Vec<i32>>, tolerance: usize) -> i32 {
+    reports
+        .iter()
+        .filter(|r| diagnose(r, tolerance).is_some())
+        .count() as i32
 }
 
 fn process_part1(reports: &Vec<Vec<i32>>) -> i32 {
-    reports.iter().filter(|r| is_safe(r, false)).count() as i32
+    process(reports, 0)
 }
 
 fn process_part2(reports: &Vec<Vec<i32>>) -> i32 {
-    reports.iter().filter(|r| is_safe(r, true)).count() as i32
+    process(reports, 1)
+}
+
+fn print_unsafe(reports: &Vec<Vec<i32>>, tolerance: usize) {
+    for (i, report) in reports.iter().enumerate() {
+        if diagnose(report, tolerance).is_none() {
+            let diagnosis = diagnose(report, report.len()).unwrap();
+            println!(
+                "{}: {} (trend {:?}, remove {:?})",
+                i + 1,
+                report.iter().join(" "),
+                diagnosis.trend,
+                diagnosis.removals
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -65,12 +137,38 @@ mod tests {
         let result = process_part2(&ns);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn test_diagnose() {
+        let ns = parse(INPUT.lines());
+
+        let safe = diagnose(&ns[0], 0).unwrap();
+        assert!(safe.removals.is_empty());
+        assert_eq!(safe.trend, Some(Trend::Decreasing));
+
+        assert_eq!(
+            diagnose(&ns[3], 1),
+            Some(Diagnosis {
+                trend: Some(Trend::Increasing),
+                removals: vec![2]
+            })
+        );
+
+        assert_eq!(diagnose(&ns[1], 1), None);
+        assert_eq!(diagnose(&ns[1], 2).unwrap().removals, vec![0, 1]);
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input.lines());
 
+    if env::args().nth(1).as_deref() == Some("unsafe") {
+        let tolerance = env::args().nth(2).map_or(0, |k| k.parse().unwrap());
+        print_unsafe(&ns, tolerance);
+        return;
+    }
+
     let result_part1 = process_part1(&ns);
     println!("{}", result_part1);
 
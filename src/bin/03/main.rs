@@ -1,26 +1,194 @@
-use regex::Regex;
-use std::str::Lines;
+use std::{env, str::Lines};
+
+use itertools::Itertools;
+
+struct Machine {
+    enabled: bool,
+    total: i32,
+}
+
+type Handler = fn(&mut Machine, &[i32]);
+
+#[derive(Clone, Copy)]
+struct Instruction {
+    name: &'static str,
+    arity: usize,
+    conditional: bool,
+    handler: Handler,
+}
+
+const MUL: Instruction = Instruction {
+    name: "mul",
+    arity: 2,
+    conditional: true,
+    handler: |machine, args| machine.total += args[0] * args[1],
+};
+
+const DO: Instruction = Instruction {
+    name: "do",
+    arity: 0,
+    conditional: false,
+    handler: |machine, _| machine.enabled = true,
+};
+
+const DONT: Instruction = Instruction {
+    name: "don't",
+    arity: 0,
+    conditional: false,
+    handler: |machine, _| machine.enabled = false,
+};
+
+struct Registry(Vec<Instruction>);
+
+impl Registry {
+    fn new(instructions: &[Instruction]) -> Self {
+        let mut registry = Registry(vec![]);
+        for instruction in instructions {
+            registry.register(*instruction);
+        }
+        registry
+    }
+
+    fn register(&mut self, instruction: Instruction) {
+        self.0.retain(|i| i.name != instruction.name);
+        self.0.push(instruction);
+    }
+
+    fn get(&self, name: &str) -> Option<&Instruction> {
+        self.0.iter().find(|i| i.name == name)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Call {
+        offset: usize,
+        name: &'static str,
+        args: Vec<i32>,
+    },
+    Noise {
+        offset: usize,
+        text: &'a str,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+struct Step {
+    offset: usize,
+    name: &'static str,
+    args: Vec<i32>,
+    executed: bool,
+}
 
 fn parse(iter: Lines) -> String {
-    iter.collect::<Vec<&str>>().join("")
+    iter.collect::<Vec<&str>>().join("\n")
 }
 
-fn process_part1(instructions: &str) -> i32 {
-    let instruction_regex = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-    instruction_regex
-        .captures_iter(instructions)
-        .map(|caps| caps.extract())
-        .map(|(_, vals)| vals.map(|v| v.parse::<i32>().unwrap()))
-        .map(|[a, b]| a * b)
-        .sum()
+fn operand(s: &str) -> Option<(i32, usize)> {
+    let digits = s.bytes().take(4).take_while(u8::is_ascii_digit).count();
+    if (1..=3).contains(&digits) {
+        Some((s[..digits].parse().unwrap(), digits))
+    } else {
+        None
+    }
 }
 
-fn process_part2(instructions: &str) -> i32 {
-    let dont_regex = Regex::new(r"don't\(\).*?(do\(\))|$").unwrap();
+fn call(s: &str, instruction: &Instruction) -> Option<(Vec<i32>, usize)> {
+    let mut rest = s.strip_prefix(instruction.name)?.strip_prefix('(')?;
+    let mut args = vec![];
+    for i in 0..instruction.arity {
+        if i > 0 {
+            rest = rest.strip_prefix(',')?;
+        }
+        let (value, length) = operand(rest)?;
+        args.push(value);
+        rest = &rest[length..];
+    }
+    rest = rest.strip_prefix(')')?;
 
-    let s = dont_regex.replace_all(instructions, "").into_owned();
+    Some((args, s.len() - rest.len()))
+}
 
-    process_part1(&s)
+fn scan<'a>(input: &'a str, registry: &Registry) -> Vec<Token<'a>> {
+    let mut tokens = vec![];
+    let mut noise_start = 0;
+    let mut i = 0;
+
+    while i < input.len() {
+        let found = registry.0.iter().find_map(|instruction| {
+            call(&input[i..], instruction).map(|(args, length)| (instruction.name, args, length))
+        });
+
+        match found {
+            Some((name, args, length)) => {
+                if noise_start < i {
+                    tokens.push(Token::Noise {
+                        offset: noise_start,
+                        text: &input[noise_start..i],
+                    });
+                }
+                tokens.push(Token::Call {
+                    offset: i,
+                    name,
+                    args,
+                });
+                i += length;
+                noise_start = i;
+            }
+            None => i += input[i..].chars().next().unwrap().len_utf8(),
+        }
+    }
+
+    if noise_start < input.len() {
+        tokens.push(Token::Noise {
+            offset: noise_start,
+            text: &input[noise_start..],
+        });
+    }
+
+    tokens
+}
+
+fn execute(tokens: &[Token], registry: &Registry) -> (i32, Vec<Step>) {
+    let mut machine = Machine {
+        enabled: true,
+        total: 0,
+    };
+
+    let trace = tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Call { offset, name, args } => Some((offset, name, args)),
+            Token::Noise { .. } => None,
+        })
+        .map(|(&offset, &name, args)| {
+            let instruction = registry.get(name).unwrap();
+            let executed = machine.enabled || !instruction.conditional;
+            if executed {
+                (instruction.handler)(&mut machine, args);
+            }
+            Step {
+                offset,
+                name,
+                args: args.clone(),
+                executed,
+            }
+        })
+        .collect();
+
+    (machine.total, trace)
+}
+
+fn interpret(instructions: &str, registry: &Registry) -> (i32, Vec<Step>) {
+    execute(&scan(instructions, registry), registry)
+}
+
+fn process_part1(instructions: &str) -> i32 {
+    interpret(instructions, &Registry::new(&[MUL])).0
+}
+
+fn process_part2(instructions: &str) -> i32 {
+    interpret(instructions, &Registry::new(&[MUL, DO, DONT])).0
 }
 
 #[cfg(test)]
@@ -43,12 +211,56 @@ mod tests {
         let result = process_part2(&instructions);
         assert_eq!(result, 48);
     }
+
+    #[test]
+    fn test_trace() {
+        let instructions = parse(INPUT2.lines());
+        let (_, trace) = interpret(&instructions, &Registry::new(&[MUL, DO, DONT]));
+        assert_eq!(
+            trace
+                .iter()
+                .map(|s| (s.offset, s.name, s.executed))
+                .collect::<Vec<_>>(),
+            [
+                (1, "mul", true),
+                (20, "don't", true),
+                (28, "mul", false),
+                (48, "mul", false),
+                (59, "do", true),
+                (64, "mul", true),
+            ]
+        );
+
+        let mut registry = Registry::new(&[MUL, DO, DONT]);
+        registry.register(Instruction {
+            name: "add",
+            arity: 2,
+            conditional: true,
+            handler: |machine, args| machine.total += args[0] + args[1],
+        });
+        let (result, _) = interpret("add(1,2)mul(3,4)don't()add(5,6)mul(1234,1)", &registry);
+        assert_eq!(result, 15);
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let instructions = parse(input.lines());
 
+    if env::args().nth(1).as_deref() == Some("trace") {
+        let (_, trace) = interpret(&instructions, &Registry::new(&[MUL, DO, DONT]));
+        for step in trace {
+            println!(
+                "{}: {}({}) {}",
+                step.offset,
+                step.name,
+                step.args.iter().join(","),
+                if step.executed { "executed" } else { "skipped" }
+            );
+        }
+        return;
+    }
+
     let result_part1 = process_part1(&instructions);
     println!("{}", result_part1);
 
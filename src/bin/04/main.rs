@@ -1,73 +1,170 @@
-use std::str::Lines;
+use std::{env, fs, str::Lines};
+
+use itertools::Itertools;
+
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+const X_MAS: &str = "M.S\n.A.\nM.S";
+
+#[derive(Debug, PartialEq)]
+struct Match {
+    word: usize,
+    position: (usize, usize),
+    direction: (isize, isize),
+}
+
+#[derive(Debug, PartialEq)]
+enum TemplateError {
+    Empty,
+    Ragged(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Template(Vec<Vec<Option<char>>>);
+
+impl Template {
+    fn parse(s: &str) -> Result<Self, TemplateError> {
+        let rows: Vec<Vec<Option<char>>> = s
+            .lines()
+            .map(|row| row.chars().map(|c| (c != '.').then_some(c)).collect())
+            .collect();
+
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err(TemplateError::Empty);
+        }
+        if let Some(row) = rows.iter().position(|row| row.len() != width) {
+            return Err(TemplateError::Ragged(row));
+        }
+        Ok(Template(rows))
+    }
+
+    fn rotate(&self) -> Self {
+        let rows = self.0.len();
+        Template(
+            (0..self.0[0].len())
+                .map(|col| (0..rows).rev().map(|row| self.0[row][col]).collect())
+                .collect(),
+        )
+    }
+
+    fn reflect(&self) -> Self {
+        Template(
+            self.0
+                .iter()
+                .map(|row| row.iter().rev().copied().collect())
+                .collect(),
+        )
+    }
+
+    fn variants(&self, rotations: bool, reflections: bool) -> Vec<Template> {
+        let mut variants = vec![self.clone()];
+        if reflections {
+            variants.push(self.reflect());
+        }
+        if rotations {
+            variants = variants
+                .iter()
+                .flat_map(|v| {
+                    (0..3)
+                        .scan(v.clone(), |t, _| {
+                            *t = t.rotate();
+                            Some(t.clone())
+                        })
+                        .chain([v.clone()])
+                })
+                .collect();
+        }
+
+        let mut unique: Vec<Template> = vec![];
+        for variant in variants {
+            if !unique.contains(&variant) {
+                unique.push(variant);
+            }
+        }
+        unique
+    }
+}
 
 fn parse(iter: Lines) -> Vec<Vec<char>> {
     iter.map(|line| line.chars().collect()).collect()
 }
 
-fn windows2<const SIZE: usize>(vec2: &Vec<Vec<char>>) -> Vec<[[char; SIZE]; SIZE]> {
-    vec2.windows(SIZE)
-        .flat_map(|row_window| {
-            (0..=row_window[0].len() - SIZE).map(|i| {
-                row_window
-                    .into_iter()
-                    .map(|row| {
-                        row.iter()
-                            .skip(i)
-                            .take(SIZE)
-                            .copied()
-                            .collect::<Vec<char>>()
-                            .try_into()
-                            .unwrap()
-                    })
-                    .collect::<Vec<[char; SIZE]>>()
-                    .try_into()
-                    .unwrap()
+fn at(grid: &[Vec<char>], row: isize, col: isize) -> Option<char> {
+    grid.get(usize::try_from(row).ok()?)?
+        .get(usize::try_from(col).ok()?)
+        .copied()
+}
+
+fn find_words(grid: &[Vec<char>], words: &[&str]) -> Vec<Match> {
+    let cells = (0..grid.len()).flat_map(|row| (0..grid[row].len()).map(move |col| (row, col)));
+
+    cells
+        .cartesian_product(words.iter().enumerate())
+        .cartesian_product(DIRECTIONS)
+        .filter(|&(((row, col), (_, word)), (dr, dc))| {
+            word.chars().enumerate().all(|(i, c)| {
+                let i = i as isize;
+                at(grid, row as isize + dr * i, col as isize + dc * i) == Some(c)
             })
         })
+        .map(|((position, (word, _)), direction)| Match {
+            word,
+            position,
+            direction,
+        })
         .collect()
 }
 
-fn process_part1(nss: &Vec<Vec<char>>) -> i32 {
-    let horizontal_lines = nss.iter().map(|row| row.iter().collect::<String>());
-    let vertical_lines =
-        (0..nss[0].len()).map(|i| nss.iter().map(|row| row[i]).collect::<String>());
-
-    let lines = horizontal_lines.chain(vertical_lines);
-    let across_count = lines
-        .map(|s| s.matches("XMAS").chain(s.matches("SAMX")).count() as i32)
-        .sum::<i32>();
-
-    let ws = windows2::<4>(nss);
-    let mut diagonal_count = 0;
-    for w in ws {
-        diagonal_count += match w {
-            [['X', _, _, _], [_, 'M', _, _], [_, _, 'A', _], [_, _, _, 'S']]
-            | [['S', _, _, _], [_, 'A', _, _], [_, _, 'M', _], [_, _, _, 'X']] => 1,
-            _ => 0,
-        };
-        diagonal_count += match w {
-            [[_, _, _, 'X'], [_, _, 'M', _], [_, 'A', _, _], ['S', _, _, _]]
-            | [[_, _, _, 'S'], [_, _, 'A', _], [_, 'M', _, _], ['X', _, _, _]] => 1,
-            _ => 0,
-        };
-    }
+fn find_template(grid: &[Vec<char>], template: &Template) -> Vec<(usize, usize)> {
+    let (height, width) = (template.0.len(), template.0[0].len());
+    let rows = grid.len().checked_sub(height).map_or(0, |n| n + 1);
+    let cols = grid
+        .first()
+        .and_then(|row| row.len().checked_sub(width))
+        .map_or(0, |n| n + 1);
+
+    (0..rows)
+        .cartesian_product(0..cols)
+        .filter(|&(row, col)| {
+            template.0.iter().enumerate().all(|(r, pattern)| {
+                pattern
+                    .iter()
+                    .enumerate()
+                    .all(|(c, p)| p.is_none_or(|p| grid[row + r][col + c] == p))
+            })
+        })
+        .collect()
+}
 
-    across_count + diagonal_count
+fn find_templates(
+    grid: &[Vec<char>],
+    template: &Template,
+    rotations: bool,
+    reflections: bool,
+) -> Vec<(usize, usize)> {
+    template
+        .variants(rotations, reflections)
+        .iter()
+        .flat_map(|variant| find_template(grid, variant))
+        .collect()
+}
+
+fn process_part1(nss: &Vec<Vec<char>>) -> i32 {
+    find_words(nss, &["XMAS"]).len() as i32
 }
 
 fn process_part2(nss: &Vec<Vec<char>>) -> i32 {
-    let ws = windows2::<3>(nss);
-    let mut count = 0;
-    for w in ws {
-        count += match w {
-            [['M', _, 'M'], [_, 'A', _], ['S', _, 'S']]
-            | [['S', _, 'M'], [_, 'A', _], ['S', _, 'M']]
-            | [['S', _, 'S'], [_, 'A', _], ['M', _, 'M']]
-            | [['M', _, 'S'], [_, 'A', _], ['M', _, 'S']] => 1,
-            _ => 0,
-        };
-    }
-    count
+    find_templates(nss, &Template::parse(X_MAS).unwrap(), true, true).len() as i32
 }
 
 #[cfg(test)]
@@ -89,15 +186,98 @@ mod tests {
         let result = process_part2(&ns);
         assert_eq!(result, 9);
     }
+
+    #[test]
+    fn test_search() {
+        let ns = parse("XMASAMX\n.M....M\n..A.X.A".lines());
+        assert_eq!(
+            find_words(&ns, &["XMA", "SAM"]),
+            [
+                Match {
+                    word: 0,
+                    position: (0, 0),
+                    direction: (0, 1)
+                },
+                Match {
+                    word: 0,
+                    position: (0, 0),
+                    direction: (1, 1)
+                },
+                Match {
+                    word: 1,
+                    position: (0, 3),
+                    direction: (0, -1)
+                },
+                Match {
+                    word: 1,
+                    position: (0, 3),
+                    direction: (0, 1)
+                },
+                Match {
+                    word: 0,
+                    position: (0, 6),
+                    direction: (0, -1)
+                },
+                Match {
+                    word: 0,
+                    position: (0, 6),
+                    direction: (1, 0)
+                },
+            ]
+        );
+
+        assert_eq!(
+            Template::parse(X_MAS).unwrap().variants(true, true).len(),
+            4
+        );
+        assert_eq!(
+            Template::parse(X_MAS).unwrap().variants(false, true).len(),
+            2
+        );
+        assert_eq!(
+            Template::parse("AB\n..")
+                .unwrap()
+                .variants(true, false)
+                .len(),
+            4
+        );
+        assert_eq!(Template::parse(""), Err(TemplateError::Empty));
+        assert_eq!(Template::parse("AB\nA"), Err(TemplateError::Ragged(1)));
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input.lines());
 
-    let result_part1 = process_part1(&ns);
-    println!("{}", result_part1);
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("words") => {
+            let words = args[1..].iter().map(String::as_str).collect::<Vec<_>>();
+            for m in find_words(&ns, &words) {
+                println!("{} {:?} {:?}", words[m.word], m.position, m.direction);
+            }
+        }
+        Some("template") => {
+            let template = match Template::parse(&fs::read_to_string(&args[1]).unwrap()) {
+                Ok(template) => template,
+                Err(e) => {
+                    eprintln!("invalid template: {:?}", e);
+                    return;
+                }
+            };
+            let rotations = args.iter().any(|a| a == "--rotate");
+            let reflections = args.iter().any(|a| a == "--reflect");
+            for position in find_templates(&ns, &template, rotations, reflections) {
+                println!("{:?}", position);
+            }
+        }
+        _ => {
+            let result_part1 = process_part1(&ns);
+            println!("{}", result_part1);
 
-    let result_part2 = process_part2(&ns);
-    println!("{}", result_part2);
+            let result_part2 = process_part2(&ns);
+            println!("{}", result_part2);
+        }
+    }
 }
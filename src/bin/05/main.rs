@@ -1,8 +1,12 @@
-use std::cmp::Ordering;
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+};
 
+use itertools::Itertools;
 use regex::Regex;
 
-fn parse(input: &str) -> (Vec<(i32, i32)>, Vec<Vec<i32>>) {
+fn parse(input: &str) -> (Rules, Vec<Vec<i32>>) {
     let sections = input.split("\n\n").collect::<Vec<&str>>();
     let [rules_input, pages_input] = sections.as_slice() else {
         panic!()
@@ -14,24 +18,160 @@ fn parse(input: &str) -> (Vec<(i32, i32)>, Vec<Vec<i32>>) {
         .map(|cap| cap.extract())
         .map(|(_, vals)| vals.map(|v| v.parse().unwrap()))
         .map(|[a, b]| (a, b))
-        .collect();
+        .collect::<Vec<_>>();
 
     let updates = pages_input
         .lines()
         .map(|s| s.split(',').map(|n| n.parse().unwrap()).collect())
         .collect();
 
-    (rules, updates)
+    (Rules::new(&rules), updates)
 }
 
-fn rule_sort(rules: &Vec<(i32, i32)>) -> impl FnMut(&i32, &i32) -> Ordering + '_ {
-    |a, b| {
-        if rules.contains(&(*a, *b)) {
-            Ordering::Less
-        } else if rules.contains(&(*b, *a)) {
-            Ordering::Greater
-        } else {
-            Ordering::Equal
+#[derive(Debug, PartialEq)]
+enum OrderError {
+    Cycle(Vec<i32>),
+    Ambiguous(i32, i32),
+}
+
+struct Rules(HashMap<i32, HashSet<i32>>);
+
+impl Rules {
+    fn new(pairs: &[(i32, i32)]) -> Self {
+        Rules(pairs.iter().fold(HashMap::new(), |mut acc, &(a, b)| {
+            acc.entry(a).or_insert_with(HashSet::new).insert(b);
+            acc
+        }))
+    }
+
+    fn precedes(&self, a: i32, b: i32) -> bool {
+        self.0.get(&a).is_some_and(|after| after.contains(&b))
+    }
+}
+
+fn cycle(rules: &Rules, remaining: &[i32]) -> Vec<i32> {
+    // every remaining page still has a remaining predecessor, so walking
+    // backwards must eventually revisit a page
+    let mut path = vec![remaining[0]];
+    loop {
+        let current = *path.last().unwrap();
+        let previous = *remaining
+            .iter()
+            .find(|&&p| rules.precedes(p, current))
+            .unwrap();
+        if let Some(start) = path.iter().position(|&p| p == previous) {
+            return path[start..].iter().rev().copied().collect();
+        }
+        path.push(previous);
+    }
+}
+
+fn topological_sort(rules: &Rules, update: &[i32]) -> Result<Vec<i32>, OrderError> {
+    let mut indegree = update
+        .iter()
+        .map(|&b| update.iter().filter(|&&a| rules.precedes(a, b)).count())
+        .collect::<Vec<_>>();
+    let mut placed = vec![false; update.len()];
+    let mut order = Vec::with_capacity(update.len());
+
+    while order.len() < update.len() {
+        let ready = (0..update.len())
+            .filter(|&i| !placed[i] && indegree[i] == 0)
+            .collect::<Vec<_>>();
+        match ready[..] {
+            [] => {
+                let remaining = (0..update.len())
+                    .filter(|&i| !placed[i])
+                    .map(|i| update[i])
+                    .collect::<Vec<_>>();
+                return Err(OrderError::Cycle(cycle(rules, &remaining)));
+            }
+            [i] => {
+                placed[i] = true;
+                order.push(update[i]);
+                for (j, &b) in update.iter().enumerate() {
+                    if rules.precedes(update[i], b) {
+                        indegree[j] -= 1;
+                    }
+                }
+            }
+            [i, j, ..] => return Err(OrderError::Ambiguous(update[i], update[j])),
+        }
+    }
+
+    Ok(order)
+}
+
+#[derive(Debug, PartialEq)]
+struct Violation {
+    rule: (i32, i32),
+    positions: (usize, usize),
+}
+
+fn violations(rules: &Rules, update: &[i32]) -> Vec<Violation> {
+    (0..update.len())
+        .flat_map(|i| (i + 1..update.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| rules.precedes(update[j], update[i]))
+        .map(|(i, j)| Violation {
+            rule: (update[j], update[i]),
+            positions: (j, i),
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+struct Explanation {
+    violations: Vec<Violation>,
+    moves: Option<usize>,
+    corrected: Result<Vec<i32>, OrderError>,
+}
+
+fn explain(rules: &Rules, update: &[i32]) -> Explanation {
+    let violations = violations(rules, update);
+    let corrected = topological_sort(rules, update);
+
+    // pages outside the longest run already in corrected order each need one move
+    let moves = corrected.as_ref().ok().map(|corrected| {
+        let mut tails: Vec<usize> = vec![];
+        for page in update {
+            let rank = corrected.iter().position(|p| p == page).unwrap();
+            let i = tails.partition_point(|&t| t < rank);
+            if i == tails.len() {
+                tails.push(rank);
+            } else {
+                tails[i] = rank;
+            }
+        }
+        update.len() - tails.len()
+    });
+
+    Explanation {
+        violations,
+        moves,
+        corrected,
+    }
+}
+
+fn print_violations(rules: &Rules, updates: &Vec<Vec<i32>>) {
+    for (i, update) in updates.iter().enumerate() {
+        let explanation = explain(rules, update);
+        if explanation.violations.is_empty() {
+            continue;
+        }
+
+        println!("{}: {}", i + 1, update.iter().join(","));
+        for v in explanation.violations {
+            println!(
+                "  {}|{} broken: {} at {}, {} at {}",
+                v.rule.0, v.rule.1, v.rule.0, v.positions.0, v.rule.1, v.positions.1
+            );
+        }
+        match (explanation.moves, explanation.corrected) {
+            (Some(moves), Ok(corrected)) => {
+                println!("  {} move(s) to {}", moves, corrected.iter().join(","))
+            }
+            (_, Err(e)) => println!("  cannot be ordered: {:?}", e),
+            (None, Ok(_)) => unreachable!(),
         }
     }
 }
@@ -43,26 +183,18 @@ where
     ns.get(ns.len() / 2).unwrap().to_owned()
 }
 
-fn process(rules: &Vec<(i32, i32)>, updates: &Vec<Vec<i32>>) -> (i32, i32) {
-    let (correct, incorrect): (Vec<Option<i32>>, Vec<Option<i32>>) = updates
-        .iter()
-        .map(|u: &Vec<i32>| {
-            let mut update = u.to_owned();
-
-            update.sort_by(rule_sort(rules));
-
-            if u == &update {
-                (Some(middle(u)), None)
-            } else {
-                (None, Some(middle(&update)))
-            }
-        })
-        .unzip();
+fn process(rules: &Rules, updates: &Vec<Vec<i32>>) -> Result<(i32, i32), OrderError> {
+    let mut correct = 0;
+    let mut incorrect = 0;
+    for update in updates {
+        if violations(rules, update).is_empty() {
+            correct += middle(update);
+        } else {
+            incorrect += middle(&topological_sort(rules, update)?);
+        }
+    }
 
-    (
-        correct.iter().flatten().sum(),
-        incorrect.iter().flatten().sum(),
-    )
+    Ok((correct, incorrect))
 }
 
 #[cfg(test)]
@@ -74,23 +206,103 @@ mod tests {
     #[test]
     fn test_part1() {
         let (rules, updates) = parse(INPUT);
-        let (result, _) = process(&rules, &updates);
+        let (result, _) = process(&rules, &updates).unwrap();
         assert_eq!(result, 143);
     }
 
     #[test]
     fn test_part2() {
         let (rules, updates) = parse(INPUT);
-        let (_, result) = process(&rules, &updates);
+        let (_, result) = process(&rules, &updates).unwrap();
         assert_eq!(result, 123);
     }
+
+    #[test]
+    fn test_unrelated_pages() {
+        let rules = Rules::new(&[(1, 2), (2, 3)]);
+        assert_eq!(
+            process(&rules, &vec![vec![1, 2, 3, 4], vec![3, 1]]),
+            Ok((4, 0))
+        );
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let (rules, _) = parse(INPUT);
+        assert_eq!(
+            topological_sort(&rules, &[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+
+        let rules = Rules::new(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        assert_eq!(
+            topological_sort(&rules, &[4, 1, 2, 3]),
+            Err(OrderError::Cycle(vec![2, 3, 1]))
+        );
+        assert_eq!(
+            topological_sort(&rules, &[2, 4, 5]),
+            Err(OrderError::Ambiguous(2, 4))
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let (rules, updates) = parse(INPUT);
+        assert!(explain(&rules, &updates[0]).violations.is_empty());
+
+        assert_eq!(
+            explain(&rules, &updates[5]),
+            Explanation {
+                violations: vec![
+                    Violation {
+                        rule: (75, 13),
+                        positions: (2, 1)
+                    },
+                    Violation {
+                        rule: (29, 13),
+                        positions: (3, 1)
+                    },
+                    Violation {
+                        rule: (47, 13),
+                        positions: (4, 1)
+                    },
+                    Violation {
+                        rule: (47, 29),
+                        positions: (4, 3)
+                    },
+                ],
+                moves: Some(2),
+                corrected: Ok(vec![97, 75, 47, 29, 13]),
+            }
+        );
+
+        let rules = Rules::new(&[(1, 2), (2, 3)]);
+        assert_eq!(
+            explain(&rules, &[1, 2, 3, 4]),
+            Explanation {
+                violations: vec![],
+                moves: None,
+                corrected: Err(OrderError::Ambiguous(1, 4)),
+            }
+        );
+        assert_eq!(explain(&rules, &[2, 1, 4]).violations.len(), 1);
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let (rules, updates) = parse(input);
 
-    let (result_part1, result_part2) = process(&rules, &updates);
-    println!("{}", result_part1);
-    println!("{}", result_part2);
+    if env::args().nth(1).as_deref() == Some("explain") {
+        print_violations(&rules, &updates);
+        return;
+    }
+
+    match process(&rules, &updates) {
+        Ok((result_part1, result_part2)) => {
+            println!("{}", result_part1);
+            println!("{}", result_part2);
+        }
+        Err(e) => eprintln!("cannot order update: {:?}", e),
+    }
 }
@@ -1,139 +1,220 @@
-use std::{
-    collections::HashSet,
-    hash::Hash,
-    iter::{once, Cycle},
-    slice::Iter,
-};
+use std::collections::{HashMap, HashSet};
 
-use itertools::Itertools;
+type Cell = (usize, usize);
 
-const DIRECTIONS: [Point; 4] = [Point(-1, 0), Point(0, 1), Point(1, 0), Point(0, -1)];
-
-#[derive(Clone)]
-struct Guard<'a> {
-    position: Point,
-    direction: Cycle<Iter<'a, Point>>,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
 }
 
-impl PartialEq for Guard<'_> {
-    fn eq(&self, other: &Self) -> bool {
-        self.position == other.position
-            && self.direction.clone().peekable().peek() == other.direction.clone().peekable().peek()
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    fn from_glyph(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
     }
-}
 
-impl Eq for Guard<'_> {}
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Right => (0, 1),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+        }
+    }
 
-impl Hash for Guard<'_> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.position.hash(state);
-        self.direction.clone().peekable().peek().hash(state);
+    fn turn(self) -> Self {
+        Direction::ALL[(self as usize + 1) % 4]
     }
 }
 
-#[derive(Copy, Clone)]
-struct Point(i32, i32);
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Guard {
+    position: Cell,
+    direction: Direction,
+}
+
+#[derive(Debug, PartialEq)]
+enum Patrol {
+    Exit { path: Vec<Guard>, exit: Cell },
+    Loop { path: Vec<Guard>, cycle: Vec<Guard> },
+}
 
-impl std::ops::Add for Point {
-    fn add(self, rhs: Self) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
+impl Patrol {
+    fn path(&self) -> &[Guard] {
+        match self {
+            Patrol::Exit { path, .. } | Patrol::Loop { path, .. } => path,
+        }
     }
+}
 
-    type Output = Point;
+struct Map {
+    width: usize,
+    height: usize,
+    obstacles: Vec<Vec<bool>>,
+    // the cell the guard stops on before the next obstacle, or None if it walks off the map
+    jumps: Vec<Vec<[Option<Cell>; 4]>>,
 }
 
-impl PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0 && self.1 == other.1
+impl Map {
+    fn new(obstacles: Vec<Vec<bool>>) -> Self {
+        let (width, height) = (obstacles.first().map_or(0, Vec::len), obstacles.len());
+        let mut map = Map {
+            width,
+            height,
+            obstacles,
+            jumps: vec![vec![[None; 4]; width]; height],
+        };
+
+        for direction in Direction::ALL {
+            let (dr, dc) = direction.offset();
+            // fill cells nearest the far edge first so each can reuse its neighbour's jump
+            let rows = (0..height).map(|r| if dr > 0 { height - 1 - r } else { r });
+            for r in rows {
+                let cols = (0..width).map(|c| if dc > 0 { width - 1 - c } else { c });
+                for c in cols {
+                    map.jumps[r][c][direction as usize] = match map.step((r, c), direction) {
+                        None => None,
+                        Some((nr, nc)) if map.obstacles[nr][nc] => Some((r, c)),
+                        Some((nr, nc)) => map.jumps[nr][nc][direction as usize],
+                    };
+                }
+            }
+        }
+
+        map
+    }
+
+    fn step(&self, (r, c): Cell, direction: Direction) -> Option<Cell> {
+        let (dr, dc) = direction.offset();
+        let (r, c) = (r.checked_add_signed(dr)?, c.checked_add_signed(dc)?);
+        (r < self.height && c < self.width).then_some((r, c))
     }
-}
 
-impl Eq for Point {}
+    fn jump(&self, guard: Guard, extra: Option<Cell>) -> Option<Cell> {
+        let (r, c) = guard.position;
+        let stop = self.jumps[r][c][guard.direction as usize];
+
+        let Some(n) = extra.and_then(|o| distance(guard.position, o, guard.direction)) else {
+            return stop;
+        };
+        if stop.is_some_and(|s| distance(guard.position, s, guard.direction).unwrap_or(0) < n) {
+            return stop;
+        }
+
+        let (dr, dc) = guard.direction.offset();
+        let n = n as isize - 1;
+        Some((
+            (r as isize + dr * n) as usize,
+            (c as isize + dc * n) as usize,
+        ))
+    }
 
-impl Hash for Point {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.0.hash(state);
-        self.1.hash(state);
+    fn loops(&self, mut guard: Guard, extra: Option<Cell>) -> bool {
+        let mut turns = HashSet::new();
+        while let Some(position) = self.jump(guard, extra) {
+            guard.position = position;
+            if !turns.insert(guard) {
+                return true;
+            }
+            guard.direction = guard.direction.turn();
+        }
+        false
+    }
+
+    fn patrol(&self, mut guard: Guard) -> Patrol {
+        let mut path = vec![guard];
+        let mut turns = HashMap::new();
+        loop {
+            let stop = self.jump(guard, None);
+            while Some(guard.position) != stop {
+                match self.step(guard.position, guard.direction) {
+                    Some(position) => guard.position = position,
+                    None => {
+                        return Patrol::Exit {
+                            path,
+                            exit: guard.position,
+                        }
+                    }
+                }
+                path.push(guard);
+            }
+            if let Some(&start) = turns.get(&guard) {
+                path.pop();
+                let cycle = path[start..].to_vec();
+                return Patrol::Loop { path, cycle };
+            }
+            turns.insert(guard, path.len() - 1);
+            guard.direction = guard.direction.turn();
+            path.push(guard);
+        }
     }
 }
 
-fn parse(input: &str) -> (Guard, Vec<Point>, usize) {
+fn distance(from: Cell, to: Cell, direction: Direction) -> Option<usize> {
+    let (dr, dc) = direction.offset();
+    let (r, c) = (
+        to.0 as isize - from.0 as isize,
+        to.1 as isize - from.1 as isize,
+    );
+    let n = r * dr + c * dc;
+    (n > 0 && (r, c) == (n * dr, n * dc)).then_some(n as usize)
+}
+
+fn parse(input: &str) -> (Guard, Map) {
     let obstacles = input
         .lines()
-        .enumerate()
-        .flat_map(|(row, s)| {
-            s.match_indices('#')
-                .map(move |(col, _)| Point(row as i32, col as i32))
-        })
+        .map(|s| s.chars().map(|c| c == '#').collect())
         .collect();
 
-    let position = input
+    let guard = input
         .lines()
         .enumerate()
-        .find_map(|(row, s)| s.find('^').map(|col| Point(row as i32, col as i32)))
+        .find_map(|(row, s)| {
+            s.chars().enumerate().find_map(|(col, c)| {
+                Direction::from_glyph(c).map(|direction| Guard {
+                    position: (row, col),
+                    direction,
+                })
+            })
+        })
         .unwrap();
-    let direction = DIRECTIONS.iter().cycle();
-    let guard = Guard {
-        position,
-        direction,
-    };
 
-    (guard, obstacles, input.lines().count())
-}
-
-fn path<'a>(
-    mut guard: Guard<'a>,
-    obstacles: &'a Vec<Point>,
-    size: usize,
-) -> impl Iterator<Item = Guard<'a>> + 'a {
-    (0..)
-        .map(move |_| {
-            let next_position =
-                guard.position + **guard.direction.clone().peekable().peek().unwrap();
-            match next_position {
-                Point(x, _) if x < 0 || x >= size as i32 => None,
-                Point(_, y) if y < 0 || y >= size as i32 => None,
-                p if obstacles.contains(&p) => {
-                    guard.direction.next();
-                    Some(guard.clone())
-                }
-                _ => {
-                    guard.position = next_position;
-                    Some(guard.clone())
-                }
-            }
-        })
-        .take_while(Option::is_some)
-        .flatten()
+    (guard, Map::new(obstacles))
 }
 
-fn process_part1(guard: Guard, obstacles: &Vec<Point>, size: usize) -> usize {
-    let start_position = guard.position;
-    path(guard, obstacles, size)
+fn process_part1(guard: Guard, map: &Map) -> usize {
+    map.patrol(guard)
+        .path()
+        .iter()
         .map(|g| g.position)
-        .chain(once(start_position))
         .collect::<HashSet<_>>()
         .len()
 }
 
-fn process_part2(guard: Guard, obstacles: &Vec<Point>, size: usize) -> usize {
-    let original_path = path(guard.clone(), obstacles, size)
-        .map(|g| g.position)
-        .collect::<HashSet<_>>();
-
-    original_path
-        .iter()
-        .filter(|path_position| {
-            let new_obstacles = obstacles
-                .iter()
-                .cloned()
-                .chain(once(**path_position))
-                .collect::<Vec<_>>();
-
-            let is_loop = !path(guard.clone(), &new_obstacles, size).all_unique();
+fn process_part2(guard: Guard, map: &Map) -> usize {
+    let mut tried = HashSet::from([guard.position]);
 
-            is_loop
-        })
+    // resume from the state just before the guard first reaches each cell
+    map.patrol(guard)
+        .path()
+        .windows(2)
+        .filter(|w| tried.insert(w[1].position))
+        .filter(|w| map.loops(w[0], Some(w[1].position)))
         .count()
 }
 
@@ -145,26 +226,71 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let (guard, obstacles, size) = parse(INPUT);
-        let result = process_part1(guard, &obstacles, size);
+        let (guard, map) = parse(INPUT);
+        let result = process_part1(guard, &map);
         assert_eq!(result, 41);
     }
 
     #[test]
     fn test_part2() {
-        let (guard, obstacles, size) = parse(INPUT);
-        let result = process_part2(guard, &obstacles, size);
+        let (guard, map) = parse(INPUT);
+        let result = process_part2(guard, &map);
         assert_eq!(result, 6);
     }
+
+    #[test]
+    fn test_patrol() {
+        let (guard, map) = parse("..>.#\n.....");
+        assert_eq!(
+            map.patrol(guard),
+            Patrol::Exit {
+                path: vec![
+                    guard,
+                    Guard {
+                        position: (0, 3),
+                        direction: Direction::Right
+                    },
+                    Guard {
+                        position: (0, 3),
+                        direction: Direction::Down
+                    },
+                    Guard {
+                        position: (1, 3),
+                        direction: Direction::Down
+                    },
+                ],
+                exit: (1, 3),
+            }
+        );
+
+        let (guard, map) = parse(".#..\n...#\n#^..\n..#.");
+        let Patrol::Loop { path, cycle } = map.patrol(guard) else {
+            panic!()
+        };
+        assert_eq!(path.len(), 9);
+        assert_eq!(
+            cycle.iter().map(|g| g.position).collect::<Vec<_>>(),
+            [
+                (1, 1),
+                (1, 1),
+                (1, 2),
+                (1, 2),
+                (2, 2),
+                (2, 2),
+                (2, 1),
+                (2, 1)
+            ]
+        );
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
-    let (guard, obstacles, size) = parse(input);
+    let (guard, map) = parse(input);
 
-    let result_part1 = process_part1(guard.clone(), &obstacles, size);
+    let result_part1 = process_part1(guard, &map);
     println!("{}", result_part1);
 
-    let result_part2 = process_part2(guard, &obstacles, size);
+    let result_part2 = process_part2(guard, &map);
     println!("{}", result_part2);
 }
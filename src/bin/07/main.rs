@@ -1,10 +1,85 @@
 use std::{
-    iter::once,
-    ops::{Add, Mul},
-    str::Lines,
+    env,
+    fmt::{Debug, Display},
+    str::{FromStr, Lines},
 };
 
-fn parse(iter: Lines) -> Vec<(i64, Vec<i64>)> {
+use num_integer::Integer;
+use num_traits::{CheckedAdd, CheckedMul};
+
+trait Number:
+    Integer + Clone + Display + From<u8> + FromStr<Err: Debug> + CheckedAdd + CheckedMul
+{
+}
+
+impl<T> Number for T where
+    T: Integer + Clone + Display + From<u8> + FromStr<Err: Debug> + CheckedAdd + CheckedMul
+{
+}
+
+// what the left operand must have been, given the result and the right operand
+enum Undo<T> {
+    Exactly(T),
+    Anything,
+}
+
+struct Operator<T> {
+    symbol: &'static str,
+    apply: fn(&T, &T) -> Option<T>,
+    undo: fn(&T, &T) -> Option<Undo<T>>,
+}
+
+impl<T> Clone for Operator<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Operator<T> {}
+
+impl<T: Number> Operator<T> {
+    const ADD: Self = Operator {
+        symbol: "+",
+        apply: |a, b| a.checked_add(b),
+        undo: |target, b| (target >= b).then(|| Undo::Exactly(target.clone() - b.clone())),
+    };
+
+    const MUL: Self = Operator {
+        symbol: "*",
+        apply: |a, b| a.checked_mul(b),
+        undo: |target, b| {
+            if b.is_zero() {
+                // anything times zero is zero
+                target.is_zero().then_some(Undo::Anything)
+            } else {
+                target
+                    .is_multiple_of(b)
+                    .then(|| Undo::Exactly(target.clone() / b.clone()))
+            }
+        },
+    };
+
+    const CONCAT: Self = Operator {
+        symbol: "||",
+        apply: |a, b| a.checked_mul(&shift(b)?)?.checked_add(b),
+        undo: |target, b| {
+            let (rest, suffix) = target.div_rem(&shift(b)?);
+            (suffix == *b).then_some(Undo::Exactly(rest))
+        },
+    };
+}
+
+// the power of ten just above b, or None if that doesn't fit in T
+fn shift<T: Number>(b: &T) -> Option<T> {
+    let ten = T::from(10);
+    let mut power = ten.clone();
+    while power <= *b {
+        power = power.checked_mul(&ten)?;
+    }
+    Some(power)
+}
+
+fn parse<T: Number>(iter: Lines) -> Vec<(T, Vec<T>)> {
     iter.map(|s| {
         let [left, right] = s.split(':').collect::<Vec<_>>()[..] else {
             panic!();
@@ -22,73 +97,193 @@ fn parse(iter: Lines) -> Vec<(i64, Vec<i64>)> {
     .collect()
 }
 
-fn is_solvable(test_value: i64, operators: &Vec<fn(i64, i64) -> i64>, values: &Vec<i64>) -> bool {
-    match values[..] {
-        [v, ..] if v > test_value => false,
-        [v] => test_value == v,
-        [a, b, ..] => operators.iter().any(|op| {
-            is_solvable(
-                test_value,
-                operators,
-                &once(op(a, b))
-                    .chain(values.iter().skip(2).cloned())
-                    .collect(),
-            )
-        }),
-        _ => panic!(),
+// a test value of None accepts any result, which is what undoing a multiplication by zero leaves
+fn search<'a, T: Number>(
+    test_value: Option<T>,
+    values: &'a [T],
+    operators: &'a [Operator<T>],
+) -> Box<dyn Iterator<Item = Vec<&'a Operator<T>>> + 'a> {
+    // work backwards from the test value, undoing the last operator each time
+    match values {
+        [v] => Box::new(
+            test_value
+                .is_none_or(|t| *v == t)
+                .then(Vec::new)
+                .into_iter(),
+        ),
+        [rest @ .., last] => Box::new(
+            operators
+                .iter()
+                .filter_map(move |op| match &test_value {
+                    None => Some((op, None)),
+                    Some(t) => match (op.undo)(t, last)? {
+                        Undo::Exactly(t) => Some((op, Some(t))),
+                        Undo::Anything => Some((op, None)),
+                    },
+                })
+                .flat_map(move |(op, t)| {
+                    search(t, rest, operators).map(move |mut ops| {
+                        ops.push(op);
+                        ops
+                    })
+                }),
+        ),
+        [] => panic!(),
     }
 }
 
-fn process_part1(ns: &Vec<(i64, Vec<i64>)>) -> i64 {
-    ns.iter()
-        .filter(|(test_value, values)| is_solvable(*test_value, &vec![Add::add, Mul::mul], values))
-        .map(|(test_value, _)| test_value)
-        .sum()
+fn evaluate<T: Number>(values: &[T], operators: &[&Operator<T>]) -> Option<T> {
+    operators
+        .iter()
+        .zip(&values[1..])
+        .try_fold(values[0].clone(), |acc, (op, v)| (op.apply)(&acc, v))
+}
+
+fn solutions<'a, T: Number>(
+    test_value: &'a T,
+    values: &'a [T],
+    operators: &'a [Operator<T>],
+) -> impl Iterator<Item = Vec<&'a Operator<T>>> + 'a {
+    // an undo only has to never miss a candidate, so confirm each one forwards
+    search(Some(test_value.clone()), values, operators)
+        .filter(move |ops| evaluate(values, ops).as_ref() == Some(test_value))
+}
+
+fn expression<T: Number>(values: &[T], operators: &[&Operator<T>]) -> String {
+    operators
+        .iter()
+        .zip(&values[1..])
+        .fold(values[0].to_string(), |acc, (op, v)| {
+            format!("{} {} {}", acc, op.symbol, v)
+        })
 }
 
-fn process_part2(ns: &Vec<(i64, Vec<i64>)>) -> i64 {
+// None if the total doesn't fit in T
+fn process<T: Number>(ns: &Vec<(T, Vec<T>)>, operators: &[Operator<T>]) -> Option<T> {
     ns.iter()
-        .filter(|(test_value, values)| {
-            is_solvable(
-                *test_value,
-                &vec![Add::add, Mul::mul, |a, b| {
-                    a * (10 as i64).pow(((b as f64).log10() + 1 as f64).floor() as u32) + b
-                }],
-                values,
-            )
+        .filter(|(test_value, values)| solutions(test_value, values, operators).next().is_some())
+        .try_fold(T::zero(), |acc, (test_value, _)| {
+            acc.checked_add(test_value)
         })
-        .map(|(test_value, _)| test_value)
-        .sum()
+}
+
+fn process_part1<T: Number>(ns: &Vec<(T, Vec<T>)>) -> Option<T> {
+    process(ns, &[Operator::ADD, Operator::MUL])
+}
+
+fn process_part2<T: Number>(ns: &Vec<(T, Vec<T>)>) -> Option<T> {
+    process(ns, &[Operator::ADD, Operator::MUL, Operator::CONCAT])
+}
+
+fn print_solutions<T: Number>(ns: &Vec<(T, Vec<T>)>, operators: &[Operator<T>]) {
+    for (test_value, values) in ns {
+        let mut all = solutions(test_value, values, operators);
+        if let Some(first) = all.next() {
+            println!(
+                "{}: {} ({} solutions)",
+                test_value,
+                expression(values, &first),
+                all.count() + 1
+            );
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use num_bigint::BigUint;
+
     use super::*;
 
     static INPUT: &str = include_str!("test_input.txt");
 
     #[test]
     fn test_part1() {
-        let ns = parse(INPUT.lines());
+        let ns = parse::<u64>(INPUT.lines());
         let result = process_part1(&ns);
-        assert_eq!(result, 3749);
+        assert_eq!(result, Some(3749));
     }
 
     #[test]
     fn test_part2() {
-        let ns = parse(INPUT.lines());
+        let ns = parse::<u64>(INPUT.lines());
         let result = process_part2(&ns);
-        assert_eq!(result, 11387);
+        assert_eq!(result, Some(11387));
+    }
+
+    #[test]
+    fn test_solutions() {
+        let values = [81, 40, 27];
+        let operators = [Operator::ADD, Operator::MUL];
+        assert_eq!(
+            solutions(&3267, &values, &operators)
+                .map(|ops| expression(&values, &ops))
+                .collect::<Vec<_>>(),
+            ["81 * 40 + 27", "81 + 40 * 27"]
+        );
+
+        let operators = [Operator::ADD, Operator::MUL, Operator::CONCAT];
+        assert_eq!(solutions(&7290, &[6, 8, 6, 15], &operators).count(), 1);
+
+        let sub = Operator {
+            symbol: "-",
+            apply: |a: &i64, b| a.checked_sub(*b),
+            undo: |target, b| target.checked_add(b).map(Undo::Exactly),
+        };
+        let operators = [Operator::ADD, sub];
+        let ops = solutions(&1, &[5, 4], &operators).next().unwrap();
+        assert_eq!(expression(&[5, 4], &ops), "5 - 4");
+
+        // a zero multiplied in wipes out whatever came before it
+        let operators = [Operator::ADD, Operator::MUL];
+        assert_eq!(
+            solutions(&7, &[3, 0, 7], &operators)
+                .map(|ops| expression(&[3, 0, 7], &ops))
+                .collect::<Vec<_>>(),
+            ["3 * 0 + 7"]
+        );
+        assert_eq!(solutions(&0, &[5, 2, 0], &operators).count(), 2);
+        assert_eq!(process_part1(&parse::<u64>("7: 3 0 7".lines())), Some(7));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(shift(&0u8), Some(10));
+        assert_eq!(shift(&99u8), Some(100));
+        assert_eq!(shift(&100u8), None);
+        assert_eq!(evaluate(&[200u8, 100], &[&Operator::ADD]), None);
+        assert_eq!(process_part2(&parse::<u8>("250: 2 5 0".lines())), Some(250));
+        assert_eq!(
+            process_part1(&parse::<u8>("200: 200\n100: 100".lines())),
+            None
+        );
+
+        let line = format!("{}: {}", "123".repeat(12), ["123"; 12].join(" "));
+        assert_eq!(
+            process_part2(&parse::<u128>(line.lines())),
+            "123".repeat(12).parse().ok()
+        );
+
+        let line = format!("{}: {}", "123".repeat(40), ["123"; 40].join(" "));
+        assert_eq!(
+            process_part2(&parse::<BigUint>(line.lines())),
+            "123".repeat(40).parse().ok()
+        );
     }
 }
 
 fn main() {
     let input = include_str!("input.txt");
-    let ns = parse(input.lines());
+    let ns = parse::<u64>(input.lines());
+
+    if env::args().nth(1).as_deref() == Some("solutions") {
+        print_solutions(&ns, &[Operator::ADD, Operator::MUL, Operator::CONCAT]);
+        return;
+    }
 
     let result_part1 = process_part1(&ns);
-    println!("{}", result_part1);
+    println!("{}", result_part1.unwrap());
 
     let result_part2 = process_part2(&ns);
-    println!("{}", result_part2);
+    println!("{}", result_part2.unwrap());
 }
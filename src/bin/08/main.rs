@@ -1,5 +1,6 @@
 use std::{
     collections::HashSet,
+    env,
     hash::Hash,
     ops::{Add, Mul, Sub},
 };
@@ -49,8 +50,30 @@ impl Hash for Point {
     }
 }
 
-fn parse(input: &str) -> (i32, Vec<Vec<Point>>) {
-    let size = input.lines().count() as i32;
+#[derive(Clone, Copy)]
+struct Bounds {
+    height: i32,
+    width: i32,
+}
+
+impl Bounds {
+    fn contains(&self, p: &Point) -> bool {
+        p.0 >= 0 && p.0 < self.height && p.1 >= 0 && p.1 < self.width
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Resonance {
+    Double,
+    Collinear,
+    Harmonics(usize),
+}
+
+fn parse(input: &str) -> (Bounds, Vec<(char, Vec<Point>)>) {
+    let bounds = Bounds {
+        height: input.lines().count() as i32,
+        width: input.lines().next().map_or(0, |s| s.chars().count()) as i32,
+    };
 
     let antennae = input
         .lines()
@@ -62,11 +85,11 @@ fn parse(input: &str) -> (i32, Vec<Vec<Point>>) {
             })
         })
         .into_group_map()
-        .values()
-        .cloned()
+        .into_iter()
+        .sorted_by_key(|(c, _)| *c)
         .collect::<Vec<_>>();
 
-    (size, antennae)
+    (bounds, antennae)
 }
 
 fn step(delta: &Point) -> Point {
@@ -74,45 +97,82 @@ fn step(delta: &Point) -> Point {
     Point(delta.0 / gcd, delta.1 / gcd)
 }
 
-fn in_bounds(size: i32) -> impl Fn(&Point) -> bool {
-    move |p| p.0 >= 0 && p.0 < size && p.1 >= 0 && p.1 < size
+fn ray(bounds: Bounds, from: Point, step: Point, limit: usize) -> impl Iterator<Item = Point> {
+    (1..)
+        .map(move |n| from + step * n)
+        .take(limit)
+        .take_while(move |p| bounds.contains(p))
 }
 
-fn process_part1(size: i32, antennae: &Vec<Vec<Point>>) -> usize {
+fn antinodes(bounds: Bounds, antennas: &[Point], resonance: Resonance) -> HashSet<Point> {
+    antennas
+        .iter()
+        .tuple_combinations()
+        .flat_map(|(&a, &b)| {
+            let (forward, backward, limit) = match resonance {
+                Resonance::Double => (b - a, a - b, 1),
+                Resonance::Harmonics(n) => (b - a, a - b, n),
+                // stepping from each antenna towards the other covers the whole line
+                Resonance::Collinear => (step(&(b - a)), step(&(a - b)), usize::MAX),
+            };
+            let (start_forward, start_backward) = match resonance {
+                Resonance::Collinear => (a, b),
+                _ => (b, a),
+            };
+
+            ray(bounds, start_forward, forward, limit).chain(ray(
+                bounds,
+                start_backward,
+                backward,
+                limit,
+            ))
+        })
+        .collect()
+}
+
+fn frequencies(
+    bounds: Bounds,
+    antennae: &[(char, Vec<Point>)],
+    resonance: Resonance,
+) -> Vec<(char, HashSet<Point>)> {
     antennae
         .iter()
-        .flat_map(|ants| ants.iter().combinations(2))
-        .flat_map(|ants| {
-            let [&a, &b] = ants[..] else { panic!() };
+        .map(|(c, antennas)| (*c, antinodes(bounds, antennas, resonance)))
+        .collect()
+}
 
-            vec![a - (b - a), b + (b - a)]
-        })
-        .filter(in_bounds(size))
+fn process(bounds: Bounds, antennae: &[(char, Vec<Point>)], resonance: Resonance) -> usize {
+    frequencies(bounds, antennae, resonance)
+        .into_iter()
+        .flat_map(|(_, points)| points)
         .collect::<HashSet<_>>()
         .len()
 }
 
-fn process_part2(size: i32, antennae: &Vec<Vec<Point>>) -> usize {
-    antennae
-        .iter()
-        .flat_map(|ants| ants.iter().combinations(2))
-        .flat_map(|ants| {
-            let [&a, &b] = ants[..] else { panic!() };
+fn process_part1(bounds: Bounds, antennae: &[(char, Vec<Point>)]) -> usize {
+    process(bounds, antennae, Resonance::Double)
+}
 
-            let step_forward = step(&(b - a));
-            let forward_points = (1..)
-                .map(move |n| a + (step_forward * n))
-                .take_while(in_bounds(size));
+fn process_part2(bounds: Bounds, antennae: &[(char, Vec<Point>)]) -> usize {
+    process(bounds, antennae, Resonance::Collinear)
+}
 
-            let step_backward = step(&(a - b));
-            let backward_points = (1..)
-                .map(move |n| b + (step_backward * n))
-                .take_while(in_bounds(size));
+fn render(bounds: Bounds, antennae: &[(char, Vec<Point>)], resonance: Resonance) -> String {
+    let mut grid = vec![vec!['.'; bounds.width as usize]; bounds.height as usize];
+    for (_, points) in frequencies(bounds, antennae, resonance) {
+        for p in points {
+            grid[p.0 as usize][p.1 as usize] = '#';
+        }
+    }
+    for (c, antennas) in antennae {
+        for p in antennas {
+            grid[p.0 as usize][p.1 as usize] = *c;
+        }
+    }
 
-            forward_points.chain(backward_points)
-        })
-        .collect::<HashSet<_>>()
-        .len()
+    grid.iter()
+        .map(|row| row.iter().collect::<String>())
+        .join("\n")
 }
 
 #[cfg(test)]
@@ -123,26 +183,72 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let (size, antennae) = parse(INPUT);
-        let result = process_part1(size, &antennae);
+        let (bounds, antennae) = parse(INPUT);
+        let result = process_part1(bounds, &antennae);
         assert_eq!(result, 14);
     }
 
     #[test]
     fn test_part2() {
-        let (size, antennae) = parse(INPUT);
-        let result = process_part2(size, &antennae);
+        let (bounds, antennae) = parse(INPUT);
+        let result = process_part2(bounds, &antennae);
         assert_eq!(result, 34);
     }
+
+    #[test]
+    fn test_resonance() {
+        let (bounds, antennae) = parse("..........\n...a......\n....a.....\n..........");
+        assert_eq!(
+            render(bounds, &antennae, Resonance::Double),
+            "..#.......\n...a......\n....a.....\n.....#...."
+        );
+        assert_eq!(
+            render(bounds, &antennae, Resonance::Harmonics(2)),
+            "..#.......\n...a......\n....a.....\n.....#...."
+        );
+
+        let (bounds, antennae) = parse(".A..A.........");
+        assert_eq!(
+            render(bounds, &antennae, Resonance::Harmonics(3)),
+            ".A..A..#..#..#"
+        );
+        assert_eq!(process(bounds, &antennae, Resonance::Collinear), 14);
+
+        let (bounds, antennae) = parse(INPUT);
+        let counts = frequencies(bounds, &antennae, Resonance::Double)
+            .into_iter()
+            .map(|(c, points)| (c, points.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [('0', 10), ('A', 5)]);
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
-    let (size, antennae) = parse(input);
+    let (bounds, antennae) = parse(input);
+
+    let resonance = match env::args().nth(2).as_deref() {
+        None | Some("collinear") => Resonance::Collinear,
+        Some("double") => Resonance::Double,
+        Some(n) => Resonance::Harmonics(n.parse().unwrap()),
+    };
+    match env::args().nth(1).as_deref() {
+        Some("render") => {
+            println!("{}", render(bounds, &antennae, resonance));
+            return;
+        }
+        Some("frequencies") => {
+            for (c, points) in frequencies(bounds, &antennae, resonance) {
+                println!("{}: {}", c, points.len());
+            }
+            return;
+        }
+        _ => {}
+    }
 
-    let result_part1 = process_part1(size, &antennae);
+    let result_part1 = process_part1(bounds, &antennae);
     println!("{}", result_part1);
 
-    let result_part2 = process_part2(size, &antennae);
+    let result_part2 = process_part2(bounds, &antennae);
     println!("{}", result_part2);
 }
@@ -1,61 +1,281 @@
-fn parse(input: &str) -> Vec<Option<usize>> {
-    input
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as usize)
-        .enumerate()
-        .flat_map(|(i, c)| {
-            if i % 2 == 0 {
-                vec![Some(i / 2); c]
-            } else {
-                vec![None; c]
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashSet},
+    env,
+    ops::Range,
+};
+
+// (start, length) of a run of blocks
+type Span = (usize, usize);
+
+struct DiskMap {
+    files: Vec<Span>,
+    gaps: Vec<Span>,
+}
+
+// start positions of free spans, indexed by span length
+struct FreeIndex([BTreeSet<usize>; 10]);
+
+impl FreeIndex {
+    fn new(gaps: &[Span]) -> Self {
+        let mut index = FreeIndex(Default::default());
+        for &(start, length) in gaps {
+            index.0[length].insert(start);
+        }
+        index
+    }
+
+    // the leftmost span of each length that fits, as (span length, start)
+    fn candidates(
+        &self,
+        length: usize,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (length.max(1)..10).filter_map(move |l| {
+            self.0[l]
+                .range(range.clone())
+                .next()
+                .map(|&start| (l, start))
+        })
+    }
+
+    fn allocate(&mut self, (span_length, start): (usize, usize), length: usize) {
+        self.0[span_length].remove(&start);
+        if span_length > length {
+            self.0[span_length - length].insert(start + length);
+        }
+    }
+}
+
+// where each file's blocks ended up, as (file id, span)
+type Layout = Vec<(usize, Span)>;
+
+trait Strategy {
+    fn name(&self) -> &'static str;
+    fn compact(&mut self, disk: &DiskMap) -> Layout;
+}
+
+struct BlockCompaction;
+
+impl Strategy for BlockCompaction {
+    fn name(&self) -> &'static str {
+        "block compaction"
+    }
+
+    fn compact(&mut self, disk: &DiskMap) -> Layout {
+        let mut remaining = disk.files.iter().map(|&(_, l)| l).collect::<Vec<_>>();
+        let mut layout = vec![];
+        let mut position = 0;
+        let Some(mut last) = remaining.len().checked_sub(1) else {
+            return layout;
+        };
+
+        for id in 0..remaining.len() {
+            if id > last {
+                break;
+            }
+            layout.push((id, (position, remaining[id])));
+            position += remaining[id];
+            remaining[id] = 0;
+
+            // fill the gap after this file with blocks taken from the end
+            let mut gap = disk.gaps.get(id).map_or(0, |&(_, l)| l);
+            while gap > 0 && last > id {
+                let moved = gap.min(remaining[last]);
+                layout.push((last, (position, moved)));
+                position += moved;
+                gap -= moved;
+                remaining[last] -= moved;
+                if remaining[last] == 0 {
+                    last -= 1;
+                }
             }
+        }
+
+        layout
+    }
+}
+
+// moves each whole file once, highest id first, into a free span on its left
+fn move_files(
+    disk: &DiskMap,
+    mut choose: impl FnMut(&FreeIndex, usize, usize) -> Option<(usize, usize)>,
+) -> Layout {
+    let mut free = FreeIndex::new(&disk.gaps);
+    let mut layout = disk.files.iter().copied().enumerate().collect::<Vec<_>>();
+
+    for (_, (start, length)) in layout.iter_mut().rev() {
+        if let Some(span) = choose(&free, *length, *start) {
+            free.allocate(span, *length);
+            *start = span.1;
+        }
+    }
+
+    layout
+}
+
+struct FirstFit;
+
+impl Strategy for FirstFit {
+    fn name(&self) -> &'static str {
+        "first fit"
+    }
+
+    fn compact(&mut self, disk: &DiskMap) -> Layout {
+        move_files(disk, |free, length, before| {
+            free.candidates(length, 0..before)
+                .min_by_key(|&(_, start)| start)
         })
-        .collect()
+    }
 }
 
-fn process_part1(blocks: &Vec<Option<usize>>) -> usize {
-    let mut revblocks = blocks.iter().rev().filter_map(|b| *b);
+struct BestFit;
+
+impl Strategy for BestFit {
+    fn name(&self) -> &'static str {
+        "best fit"
+    }
 
-    (0..blocks.iter().filter(|b| b.is_some()).count())
-        .map(|i| blocks[i].or_else(|| revblocks.next()).unwrap() * i)
-        .sum()
+    fn compact(&mut self, disk: &DiskMap) -> Layout {
+        move_files(disk, |free, length, before| {
+            free.candidates(length, 0..before).min()
+        })
+    }
 }
 
-fn process_part2(blocks: &Vec<Option<usize>>) -> usize {
-    let chunks = blocks.chunk_by(|a, b| a == b);
-    let mut spans = chunks.map(|c| (c[0], c.len())).collect::<Vec<_>>();
-    let revspans = spans
+struct WorstFit;
+
+impl Strategy for WorstFit {
+    fn name(&self) -> &'static str {
+        "worst fit"
+    }
+
+    fn compact(&mut self, disk: &DiskMap) -> Layout {
+        move_files(disk, |free, length, before| {
+            free.candidates(length, 0..before)
+                .min_by_key(|&(l, start)| (Reverse(l), start))
+        })
+    }
+}
+
+struct NextFit;
+
+impl Strategy for NextFit {
+    fn name(&self) -> &'static str {
+        "next fit"
+    }
+
+    fn compact(&mut self, disk: &DiskMap) -> Layout {
+        // carry on searching from the end of the previous move, wrapping to the start
+        let mut cursor = 0;
+        move_files(disk, |free, length, before| {
+            let span = free
+                .candidates(length, cursor.min(before)..before)
+                .min_by_key(|&(_, start)| start)
+                .or_else(|| {
+                    free.candidates(length, 0..cursor.min(before))
+                        .min_by_key(|&(_, start)| start)
+                });
+            if let Some((_, start)) = span {
+                cursor = start + length;
+            }
+            span
+        })
+    }
+}
+
+struct Report {
+    strategy: &'static str,
+    size: usize,
+    checksum: u128,
+    moved: usize,
+    fragmentation: usize,
+    layout: Layout,
+}
+
+impl Report {
+    fn render(&self) -> String {
+        let mut blocks = vec!['.'; self.size];
+        for &(id, (start, length)) in &self.layout {
+            // the puzzle's notation only has room for one digit per block
+            let c = char::from_digit((id % 10) as u32, 10).unwrap();
+            blocks[start..start + length].fill(c);
+        }
+        blocks.into_iter().collect()
+    }
+}
+
+fn simulate(disk: &DiskMap, strategy: &mut dyn Strategy) -> Report {
+    let mut layout = strategy.compact(disk);
+    layout.retain(|&(_, (_, length))| length > 0);
+    layout.sort_unstable_by_key(|&(_, (start, _))| start);
+
+    let moved = layout
+        .iter()
+        .filter(|&&(id, span)| span != disk.files[id])
+        .map(|(id, _)| id)
+        .collect::<HashSet<_>>()
+        .len();
+
+    // free spans left between blocks of data
+    let fragmentation = layout
         .iter()
-        .cloned()
-        .rev()
-        .filter_map(|(key, size)| key.and_then(|v| Some((v, size))))
-        .collect::<Vec<_>>();
-    for (key, size) in revspans {
-        let index = spans
+        .scan(0, |end, &(_, (start, length))| {
+            let hole = start > *end;
+            *end = start + length;
+            Some(hole)
+        })
+        .filter(|&hole| hole)
+        .count();
+
+    Report {
+        strategy: strategy.name(),
+        size: disk
+            .files
             .iter()
-            .position(|(s, _)| s.is_some_and(|v| v == key))
-            .unwrap();
-        spans
+            .chain(&disk.gaps)
+            .map(|&(s, l)| s + l)
+            .max()
+            .unwrap_or(0),
+        checksum: layout
             .iter()
-            .cloned()
-            .enumerate()
-            .find(|(i, (s, n))| *i < index && s.is_none() && *n >= size)
-            .inspect(|(i, (_, n))| {
-                spans.remove(index);
-                spans.insert(index, (None, size));
-                spans.remove(*i);
-                spans.insert(*i, (Some(key), size));
-                if *n > size {
-                    spans.insert(*i + 1, (None, n - size));
-                }
-            });
+            .map(|&(id, span)| blocks_checksum(id, span))
+            .sum(),
+        moved,
+        fragmentation,
+        layout,
     }
-    spans
-        .iter()
-        .flat_map(|(key, size)| vec![key; *size])
-        .enumerate()
-        .map(|(i, k)| k.unwrap_or_default() * i)
-        .sum()
+}
+
+fn parse(input: &str) -> DiskMap {
+    let mut disk = DiskMap {
+        files: vec![],
+        gaps: vec![],
+    };
+    let mut position = 0;
+    for (i, c) in input.trim_end().chars().enumerate() {
+        let length = c.to_digit(10).unwrap() as usize;
+        if i % 2 == 0 {
+            disk.files.push((position, length));
+        } else {
+            disk.gaps.push((position, length));
+        }
+        position += length;
+    }
+    disk
+}
+
+fn blocks_checksum(id: usize, (start, length): Span) -> u128 {
+    let positions = length * start + length * length.saturating_sub(1) / 2;
+    id as u128 * positions as u128
+}
+
+fn process_part1(disk: &DiskMap) -> u128 {
+    simulate(disk, &mut BlockCompaction).checksum
+}
+
+fn process_part2(disk: &DiskMap) -> u128 {
+    simulate(disk, &mut FirstFit).checksum
 }
 
 #[cfg(test)]
@@ -77,12 +297,61 @@ mod tests {
         let result = process_part2(&ns);
         assert_eq!(result, 2858);
     }
+
+    #[test]
+    fn test_strategies() {
+        let ns = parse("312410122");
+        let reports = [
+            &mut BlockCompaction as &mut dyn Strategy,
+            &mut FirstFit,
+            &mut BestFit,
+            &mut WorstFit,
+            &mut NextFit,
+        ]
+        .map(|strategy| simulate(&ns, strategy));
+
+        assert_eq!(
+            reports
+                .iter()
+                .map(|r| (r.render(), r.moved, r.fragmentation))
+                .collect::<Vec<_>>(),
+            [
+                ("000411432.......".to_string(), 3, 0),
+                ("000311442.......".to_string(), 3, 0),
+                ("0003112.....44..".to_string(), 3, 1),
+                ("000211443.......".to_string(), 3, 0),
+                ("000.114432......".to_string(), 3, 1),
+            ]
+        );
+        assert_eq!(reports[1].checksum, process_part2(&ns));
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input);
 
+    if env::args().nth(1).as_deref() == Some("compare") {
+        let strategies: [&mut dyn Strategy; 5] = [
+            &mut BlockCompaction,
+            &mut FirstFit,
+            &mut BestFit,
+            &mut WorstFit,
+            &mut NextFit,
+        ];
+        for strategy in strategies {
+            let report = simulate(&ns, strategy);
+            println!(
+                "{}: checksum {}, {} files moved, {} free spans",
+                report.strategy, report.checksum, report.moved, report.fragmentation
+            );
+            if env::args().nth(2).as_deref() == Some("--layout") {
+                println!("{}", report.render());
+            }
+        }
+        return;
+    }
+
     let result_part1 = process_part1(&ns);
     println!("{}", result_part1);
 
@@ -1,7 +1,14 @@
-use std::{collections::HashSet, str::Lines};
+use std::{collections::HashSet, env, str::Lines};
 
-fn parse(iter: Lines) -> Vec<Vec<u32>> {
-    iter.map(|s| s.chars().map(|c| c.to_digit(10).unwrap()).collect())
+type Cell = (usize, usize);
+
+struct Analysis {
+    trails: Vec<Vec<usize>>,
+    summits: Vec<Vec<HashSet<Cell>>>,
+}
+
+fn parse(iter: Lines) -> Vec<Vec<Option<u32>>> {
+    iter.map(|s| s.chars().map(|c| c.to_digit(10)).collect())
         .collect()
 }
 
@@ -17,66 +24,72 @@ fn step((x, y): (usize, usize)) -> Vec<(usize, usize)> {
     .collect()
 }
 
-fn score(start: (usize, usize), ns: &Vec<Vec<u32>>) -> usize {
-    let mut edge = vec![start];
-    let mut trail: HashSet<(usize, usize)> = HashSet::new();
-
-    while !edge.is_empty() {
-        let next_edge = edge
-            .iter()
-            .cloned()
-            .flat_map(|(erow, ecol)| {
-                step((erow, ecol))
-                    .iter()
-                    .cloned()
-                    .filter(|&(row, col)| row < ns.len() && col < ns[0].len())
-                    .filter(|&(row, col)| (ns[row][col] as i32 - ns[erow][ecol] as i32) == 1)
-                    .collect::<Vec<_>>()
-            })
-            .filter(|&e| trail.insert(e))
-            .collect::<Vec<_>>();
-
-        edge = next_edge;
-    }
+fn uphill(cell: Cell, ns: &Vec<Vec<Option<u32>>>) -> impl Iterator<Item = Cell> + '_ {
+    let height = ns[cell.0][cell.1];
+    step(cell).into_iter().filter(move |&(row, col)| {
+        let next = ns.get(row).and_then(|r| r.get(col)).copied().flatten();
+        height.is_some_and(|h| next == Some(h + 1))
+    })
+}
 
-    trail
-        .iter()
-        .filter(|&&(row, col)| ns[row][col] == 9)
-        .count()
+fn cells(ns: &Vec<Vec<Option<u32>>>) -> impl Iterator<Item = Cell> + '_ {
+    (0..ns.len()).flat_map(move |row| (0..ns[row].len()).map(move |col| (row, col)))
 }
 
-fn rating(start: (usize, usize), ns: &Vec<Vec<u32>>) -> usize {
-    let height = ns[start.0][start.1];
-    if height == 9 {
-        1
-    } else {
-        step(start)
-            .iter()
-            .filter(|&&(row, col)| row < ns.len() && col < ns[0].len())
-            .filter(|&&(row, col)| (ns[row][col] as i32 - height as i32) == 1)
-            .map(|&next| rating(next, ns))
-            .sum()
+fn analyse(ns: &Vec<Vec<Option<u32>>>) -> Analysis {
+    let mut analysis = Analysis {
+        trails: ns.iter().map(|r| vec![0; r.len()]).collect(),
+        summits: ns.iter().map(|r| vec![HashSet::new(); r.len()]).collect(),
+    };
+
+    // every step climbs by one, so summing over the cells one level up is enough
+    for height in (0..=9).rev() {
+        for (row, col) in cells(ns).filter(|&(r, c)| ns[r][c] == Some(height)) {
+            if height == 9 {
+                analysis.trails[row][col] = 1;
+                analysis.summits[row][col].insert((row, col));
+                continue;
+            }
+            for (r, c) in uphill((row, col), ns) {
+                analysis.trails[row][col] += analysis.trails[r][c];
+                let reachable = analysis.summits[r][c].clone();
+                analysis.summits[row][col].extend(reachable);
+            }
+        }
     }
+
+    analysis
 }
 
-fn starts(ns: &Vec<Vec<u32>>) -> impl Iterator<Item = (usize, usize)> + '_ {
-    (0..ns.len()).flat_map(move |row| {
-        (0..ns[0].len()).filter_map(move |col| {
-            if ns[row][col] == 0 {
-                Some((row, col))
-            } else {
-                None
-            }
+fn trails(start: Cell, ns: &Vec<Vec<Option<u32>>>, analysis: &Analysis) -> Vec<Vec<Cell>> {
+    if ns[start.0][start.1] == Some(9) {
+        return vec![vec![start]];
+    }
+
+    uphill(start, ns)
+        .filter(|&(row, col)| analysis.trails[row][col] > 0)
+        .flat_map(|next| trails(next, ns, analysis))
+        .map(|mut trail| {
+            trail.insert(0, start);
+            trail
         })
-    })
+        .collect()
+}
+
+fn starts(ns: &Vec<Vec<Option<u32>>>) -> impl Iterator<Item = Cell> + '_ {
+    cells(ns).filter(|&(row, col)| ns[row][col] == Some(0))
 }
 
-fn process_part1(ns: &Vec<Vec<u32>>) -> usize {
-    starts(ns).map(|start| score(start, ns)).sum()
+fn process_part1(ns: &Vec<Vec<Option<u32>>>) -> usize {
+    let analysis = analyse(ns);
+    starts(ns)
+        .map(|(row, col)| analysis.summits[row][col].len())
+        .sum()
 }
 
-fn process_part2(ns: &Vec<Vec<u32>>) -> usize {
-    starts(ns).map(|start| rating(start, ns)).sum()
+fn process_part2(ns: &Vec<Vec<Option<u32>>>) -> usize {
+    let analysis = analyse(ns);
+    starts(ns).map(|(row, col)| analysis.trails[row][col]).sum()
 }
 
 #[cfg(test)]
@@ -98,12 +111,48 @@ mod tests {
         let result = process_part2(&ns);
         assert_eq!(result, 81);
     }
+
+    #[test]
+    fn test_trails() {
+        let ns = parse("...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9".lines());
+        assert_eq!(process_part1(&ns), 2);
+
+        let ns = parse(".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....".lines());
+        assert_eq!(process_part2(&ns), 3);
+
+        let found = trails((0, 5), &ns, &analyse(&ns));
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().all(|t| t.len() == 10 && t[9] == (6, 2)));
+        assert_eq!(
+            found[0],
+            [
+                (0, 5),
+                (1, 5),
+                (2, 5),
+                (3, 5),
+                (4, 5),
+                (5, 5),
+                (5, 4),
+                (5, 3),
+                (5, 2),
+                (6, 2)
+            ]
+        );
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input.lines());
 
+    if env::args().nth(1).as_deref() == Some("trails") {
+        let coordinate = |n: usize| env::args().nth(n).unwrap().parse().unwrap();
+        for trail in trails((coordinate(2), coordinate(3)), &ns, &analyse(&ns)) {
+            println!("{:?}", trail);
+        }
+        return;
+    }
+
     let result_part1 = process_part1(&ns);
     println!("{}", result_part1);
 
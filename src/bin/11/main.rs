@@ -1,33 +1,62 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+};
 
+use itertools::Itertools;
+use num_bigint::BigUint;
 use num_integer::Integer;
 
-fn change(n: u64, depth: usize, cache: &mut HashMap<(u64, usize), usize>) -> usize {
-    if depth == 0 {
-        1
-    } else {
-        if depth >= 25 {
-            let cached = cache.get(&(n, depth));
-            if cached.is_some() {
-                return *cached.unwrap();
-            }
-        }
-        let r = if n == 0 {
-            change(1, depth - 1, cache)
-        } else {
-            let digits = ((n as f64).log10() + 1 as f64).floor() as u32;
-            if digits % 2 == 0 {
-                let (a, b) = n.div_rem(&10u64.pow(digits / 2));
-                change(a, depth - 1, cache) + change(b, depth - 1, cache)
-            } else {
-                change(n * 2024, depth - 1, cache)
-            }
-        };
-        if depth >= 25 {
-            cache.insert((n, depth), r);
-        }
-        r
-    }
+// stone value -> number of stones carrying it
+type Stones = HashMap<u64, BigUint>;
+
+#[derive(Debug, PartialEq)]
+struct Stats {
+    blink: usize,
+    distinct: usize,
+    total: BigUint,
+    largest: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Rule {
+    predicate: fn(u64) -> bool,
+    transform: fn(u64) -> Vec<u64>,
+}
+
+const ZERO: Rule = Rule {
+    predicate: |n| n == 0,
+    transform: |_| vec![1],
+};
+
+const SPLIT: Rule = Rule {
+    predicate: |n| digits(n) % 2 == 0,
+    transform: |n| {
+        let (a, b) = n.div_rem(&10u64.pow(digits(n) / 2));
+        vec![a, b]
+    },
+};
+
+const MULTIPLY: Rule = Rule {
+    predicate: |_| true,
+    transform: |n| vec![n * 2024],
+};
+
+const RULES: [Rule; 3] = [ZERO, SPLIT, MULTIPLY];
+
+// sparse: each row lists (column, count), and a value changes into at most two others
+type Matrix = Vec<Vec<(usize, u32)>>;
+
+fn digits(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |d| d + 1)
+}
+
+// the first matching rule applies; a stone no rule matches stays as it is
+fn change(n: u64, rules: &[Rule]) -> Vec<u64> {
+    rules
+        .iter()
+        .find(|rule| (rule.predicate)(n))
+        .map_or_else(|| vec![n], |rule| (rule.transform)(n))
 }
 
 fn parse(input: &str) -> Vec<u64> {
@@ -37,9 +66,109 @@ fn parse(input: &str) -> Vec<u64> {
         .collect()
 }
 
-fn process(ns: &Vec<u64>, blinks: usize) -> usize {
-    let mut cache = HashMap::new();
-    ns.iter().map(|n| change(*n, blinks, &mut cache)).sum()
+fn blink(stones: &Stones, rules: &[Rule]) -> Stones {
+    let mut next = Stones::new();
+    for (&n, count) in stones {
+        for m in change(n, rules) {
+            *next.entry(m).or_default() += count;
+        }
+    }
+    next
+}
+
+fn evolve<'a>(ns: &Vec<u64>, rules: &'a [Rule]) -> impl Iterator<Item = Stones> + 'a {
+    let mut stones = Stones::new();
+    for &n in ns {
+        *stones.entry(n).or_default() += 1u32;
+    }
+
+    std::iter::successors(Some(stones), |stones| Some(blink(stones, rules)))
+}
+
+fn total(stones: &Stones) -> BigUint {
+    stones.values().sum()
+}
+
+fn stats(ns: &Vec<u64>, blinks: usize, rules: &[Rule]) -> Vec<Stats> {
+    evolve(ns, rules)
+        .take(blinks + 1)
+        .enumerate()
+        .map(|(blink, stones)| Stats {
+            blink,
+            distinct: stones.len(),
+            total: total(&stones),
+            largest: stones.keys().copied().max().unwrap_or(0),
+        })
+        .collect()
+}
+
+fn process(ns: &Vec<u64>, blinks: usize, rules: &[Rule]) -> BigUint {
+    total(&evolve(ns, rules).nth(blinks).unwrap())
+}
+
+// every value reachable from the input, or None if there are more than limit
+fn closure(ns: &Vec<u64>, rules: &[Rule], limit: usize) -> Option<Vec<u64>> {
+    let mut values = ns.iter().copied().unique().collect::<Vec<_>>();
+    let mut seen = values.iter().copied().collect::<HashSet<_>>();
+    let mut i = 0;
+    while i < values.len() {
+        for m in change(values[i], rules) {
+            if seen.insert(m) {
+                values.push(m);
+            }
+        }
+        if values.len() > limit {
+            return None;
+        }
+        i += 1;
+    }
+    Some(values)
+}
+
+fn transitions(values: &[u64], rules: &[Rule]) -> Matrix {
+    let index = values
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| (n, i))
+        .collect::<HashMap<_, _>>();
+
+    values
+        .iter()
+        .map(|&n| {
+            change(n, rules)
+                .iter()
+                .map(|m| index[m])
+                .counts()
+                .into_iter()
+                .map(|(j, count)| (j, count as u32))
+                .collect()
+        })
+        .collect()
+}
+
+fn multiply(vector: &[BigUint], matrix: &Matrix) -> Vec<BigUint> {
+    let mut product = vec![BigUint::ZERO; vector.len()];
+    for (x, row) in vector.iter().zip(matrix) {
+        for &(j, count) in row {
+            product[j] += x * count;
+        }
+    }
+    product
+}
+
+fn process_matrix(ns: &Vec<u64>, blinks: usize, rules: &[Rule], limit: usize) -> Option<BigUint> {
+    let values = closure(ns, rules, limit)?;
+    let matrix = transitions(&values, rules);
+
+    let mut vector = vec![BigUint::ZERO; values.len()];
+    for n in ns {
+        vector[values.iter().position(|v| v == n).unwrap()] += 1u32;
+    }
+    // apply the power to the counts one blink at a time, since squaring would fill the matrix in
+    for _ in 0..blinks {
+        vector = multiply(&vector, &matrix);
+    }
+    Some(vector.iter().sum())
 }
 
 #[cfg(test)]
@@ -51,8 +180,54 @@ mod tests {
     #[test]
     fn test_part1() {
         let ns = parse(INPUT);
-        let result = process(&ns, 25);
-        assert_eq!(result, 55312);
+        let result = process(&ns, 25, &RULES);
+        assert_eq!(result, BigUint::from(55312u32));
+    }
+
+    #[test]
+    fn test_stats() {
+        let ns = parse(INPUT);
+        let stats = stats(&ns, 6, &RULES);
+        assert_eq!(
+            stats
+                .iter()
+                .map(|s| (s.blink, s.distinct, s.largest))
+                .collect::<Vec<_>>(),
+            [
+                (0, 2, 125),
+                (1, 3, 253000),
+                (2, 4, 14168),
+                (3, 5, 28676032),
+                (4, 8, 6032),
+                (5, 12, 1036288),
+                (6, 15, 2097446912),
+            ]
+        );
+        assert_eq!(stats[6].total, BigUint::from(22u32));
+        assert!(process(&ns, 1000, &RULES) > BigUint::from(u128::MAX));
+    }
+
+    #[test]
+    fn test_matrix() {
+        let ns = parse(INPUT);
+        assert_eq!(closure(&ns, &RULES, 1000).unwrap().len(), 76);
+        assert_eq!(closure(&ns, &RULES, 10), None);
+        assert_eq!(
+            process_matrix(&ns, 25, &RULES, 1000),
+            Some(BigUint::from(55312u32))
+        );
+
+        let double = Rule {
+            predicate: |_| true,
+            transform: |n| vec![n, n],
+        };
+        let ns = parse("0 3");
+        assert_eq!(closure(&ns, &[ZERO, double], 10).unwrap(), [0, 3, 1]);
+        assert_eq!(
+            process_matrix(&ns, 10, &[ZERO, double], 10),
+            Some(BigUint::from(1536u32))
+        );
+        assert_eq!(process(&ns, 10, &[ZERO, double]), BigUint::from(1536u32));
     }
 }
 
@@ -60,9 +235,32 @@ fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input);
 
-    let result_part1 = process(&ns, 25);
+    if env::args().nth(1).as_deref() == Some("stats") {
+        let blinks = env::args().nth(2).map_or(75, |n| n.parse().unwrap());
+        for s in stats(&ns, blinks, &RULES) {
+            println!(
+                "{}: {} stones, {} distinct values, largest {}",
+                s.blink, s.total, s.distinct, s.largest
+            );
+        }
+        return;
+    }
+
+    if env::args().nth(1).as_deref() == Some("matrix") {
+        let blinks = env::args().nth(2).map_or(75, |n| n.parse().unwrap());
+        match closure(&ns, &RULES, 10_000) {
+            Some(values) => {
+                println!("{} values reachable", values.len());
+                println!("{}", process_matrix(&ns, blinks, &RULES, 10_000).unwrap());
+            }
+            None => eprintln!("more than 10000 values reachable"),
+        }
+        return;
+    }
+
+    let result_part1 = process(&ns, 25, &RULES);
     println!("{}", result_part1);
 
-    let result_part2 = process(&ns, 75);
+    let result_part2 = process(&ns, 75, &RULES);
     println!("{}", result_part2);
 }
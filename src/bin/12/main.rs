@@ -1,121 +1,287 @@
-use std::{collections::HashSet, str::Lines};
+use std::{collections::HashMap, env, iter, str::Lines};
 
 use itertools::Itertools;
 
+type Cell = (usize, usize);
+
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+#[derive(Debug, PartialEq)]
+struct Region {
+    plant: char,
+    area: usize,
+    perimeter: usize,
+    sides: usize,
+    bounds: (Cell, Cell),
+    cells: Vec<Cell>,
+}
+
+// a corner of the cell lattice as (x, y), with y counting down the rows
+type Point = (i64, i64);
+
+#[derive(Debug, PartialEq)]
+struct Geometry {
+    outer: Vec<Point>,
+    holes: Vec<Vec<Point>>,
+    enclosed_by: Option<usize>,
+}
+
+impl Geometry {
+    fn sides(&self) -> usize {
+        self.outer.len() + self.holes.iter().map(Vec::len).sum::<usize>()
+    }
+}
+
+struct DisjointSet(Vec<usize>);
+
+impl DisjointSet {
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.0[i] != i {
+            self.0[i] = self.0[self.0[i]];
+            i = self.0[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        // keep the earlier cell as the root so regions come out in reading order
+        self.0[a.max(b)] = a.min(b);
+    }
+}
+
 fn parse(iter: Lines) -> Vec<Vec<char>> {
     iter.map(|s| s.chars().collect()).collect()
 }
 
-fn flood_fill(
-    ns: &mut Vec<Vec<(char, bool)>>,
-    row: usize,
-    col: usize,
-) -> (usize, usize, HashSet<(usize, usize)>) {
-    let (n, _) = ns[row][col];
-    ns[row][col].1 = true;
-    let neighbors = [
-        if row > 0 { Some((row - 1, col)) } else { None },
-        if col > 0 { Some((row, col - 1)) } else { None },
-        if col < ns[0].len() - 1 {
-            Some((row, col + 1))
-        } else {
-            None
-        },
-        if row < ns.len() - 1 {
-            Some((row + 1, col))
-        } else {
-            None
-        },
-    ]
-    .iter()
-    .filter_map(|x| *x)
-    .filter(|&(r, c)| ns[r][c].0 == n)
-    .collect::<Vec<_>>();
-
-    neighbors
+fn plant_at(ns: &Vec<Vec<char>>, (row, col): Cell, (dr, dc): (isize, isize)) -> Option<char> {
+    let (r, c) = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+    ns.get(r)?.get(c).copied()
+}
+
+fn fenced(ns: &Vec<Vec<char>>, cell: Cell, direction: (isize, isize)) -> bool {
+    plant_at(ns, cell, direction) != Some(ns[cell.0][cell.1])
+}
+
+fn regions(ns: &Vec<Vec<char>>) -> Vec<Region> {
+    let width = ns.first().map_or(0, Vec::len);
+    let mut set = DisjointSet((0..ns.len() * width).collect());
+    for (row, plants) in ns.iter().enumerate() {
+        for (col, &plant) in plants.iter().enumerate() {
+            if col + 1 < width && plants[col + 1] == plant {
+                set.union(row * width + col, row * width + col + 1);
+            }
+            if row + 1 < ns.len() && ns[row + 1][col] == plant {
+                set.union(row * width + col, (row + 1) * width + col);
+            }
+        }
+    }
+
+    let mut index = vec![usize::MAX; ns.len() * width];
+    let mut regions: Vec<Region> = vec![];
+    for (row, plants) in ns.iter().enumerate() {
+        for (col, &plant) in plants.iter().enumerate() {
+            let root = set.find(row * width + col);
+            if index[root] == usize::MAX {
+                index[root] = regions.len();
+                regions.push(Region {
+                    plant,
+                    area: 0,
+                    perimeter: 0,
+                    sides: 0,
+                    bounds: ((row, col), (row, col)),
+                    cells: vec![],
+                });
+            }
+
+            let region = &mut regions[index[root]];
+            region.area += 1;
+            region.cells.push((row, col));
+            region.bounds.0 .1 = region.bounds.0 .1.min(col);
+            region.bounds.1 = (row, region.bounds.1 .1.max(col));
+
+            region.perimeter += DIRECTIONS
+                .iter()
+                .filter(|&&direction| fenced(ns, (row, col), direction))
+                .count();
+
+            // every corner of the fence starts a new side
+            for i in 0..4 {
+                let (a, b) = (DIRECTIONS[i], DIRECTIONS[(i + 1) % 4]);
+                let same = |d| plant_at(ns, (row, col), d) == Some(plant);
+                let diagonal = (a.0 + b.0, a.1 + b.1);
+                if (!same(a) && !same(b)) || (same(a) && same(b) && !same(diagonal)) {
+                    region.sides += 1;
+                }
+            }
+        }
+    }
+
+    regions
+}
+
+// twice the signed area, positive for an outer boundary and negative for a hole
+fn signed_area(polygon: &[Point]) -> i64 {
+    polygon
         .iter()
-        .filter_map(|&(r, c)| {
-            if ns[r][c].1 {
-                None
-            } else {
-                Some(flood_fill(ns, r, c))
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum()
+}
+
+fn trace(ns: &Vec<Vec<char>>, region: &Region) -> Geometry {
+    // every fence as a unit edge with the region on its right
+    let mut edges: HashMap<Point, Vec<Point>> = HashMap::new();
+    let mut starts = vec![];
+    for &(row, col) in &region.cells {
+        let (x, y) = (col as i64, row as i64);
+        let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+        for (i, &direction) in DIRECTIONS.iter().enumerate() {
+            if fenced(ns, (row, col), direction) {
+                edges
+                    .entry(corners[i])
+                    .or_default()
+                    .push(corners[(i + 1) % 4]);
+                starts.push(corners[i]);
             }
-        })
-        .fold(
-            (
-                1,
-                4 - neighbors.len(),
-                [(row, col)].iter().copied().collect::<HashSet<_>>(),
-            ),
-            |(acc_a, acc_b, acc_set), (a, b, set)| {
+        }
+    }
+
+    let mut loops = vec![];
+    for start in starts {
+        let Some(&first) = edges[&start].last() else {
+            continue;
+        };
+        let mut path = vec![start];
+        let (mut from, mut at) = (start, first);
+        // where two loops touch at a corner, turn right so they stay apart
+        while (from, at) != (start, first) || path.len() == 1 {
+            let (dx, dy) = (at.0 - from.0, at.1 - from.1);
+            let next = [(-dy, dx), (dx, dy), (dy, -dx)]
+                .into_iter()
+                .map(|(dx, dy)| (at.0 + dx, at.1 + dy))
+                .find(|next| edges[&at].contains(next))
+                .unwrap();
+            edges.get_mut(&at).unwrap().retain(|&p| p != next);
+            if (at, next) != (start, first) {
+                path.push(at);
+            }
+            (from, at) = (at, next);
+        }
+
+        // keep only the vertices where the fence changes direction
+        let corners = (0..path.len())
+            .map(|i| {
                 (
-                    acc_a + a,
-                    acc_b + b,
-                    acc_set.iter().chain(set.iter()).copied().collect(),
+                    path[(i + path.len() - 1) % path.len()],
+                    path[i],
+                    path[(i + 1) % path.len()],
                 )
-            },
-        )
+            })
+            .filter(|&(a, b, c)| (b.0 - a.0, b.1 - a.1) != (c.0 - b.0, c.1 - b.1))
+            .map(|(_, b, _)| b)
+            .collect::<Vec<_>>();
+        loops.push(corners);
+    }
+
+    let (mut outer, holes): (Vec<_>, Vec<_>) = loops.into_iter().partition(|l| signed_area(l) > 0);
+    Geometry {
+        outer: outer.pop().unwrap(),
+        holes,
+        enclosed_by: None,
+    }
 }
 
-fn process_internal(ns: &Vec<Vec<char>>) -> Vec<(usize, usize, HashSet<(usize, usize)>)> {
-    let mut state = ns
+// ray casting from a cell centre, in doubled coordinates so the ray never grazes a vertex
+fn contains(polygon: &[Point], (row, col): Cell) -> bool {
+    let (x, y) = (2 * col as i64 + 1, 2 * row as i64 + 1);
+    polygon
         .iter()
-        .map(|row| row.iter().map(|v| (*v, false)).collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    let mut acc = vec![];
+        .zip(polygon.iter().cycle().skip(1))
+        .filter(|(a, b)| {
+            a.0 == b.0 && 2 * a.0 > x && (2 * a.1 - y).signum() != (2 * b.1 - y).signum()
+        })
+        .count()
+        % 2
+        == 1
+}
 
-    while let Some((r, c, _)) = state
+fn geometries(ns: &Vec<Vec<char>>, regions: &[Region]) -> Vec<Geometry> {
+    let mut geometries = regions.iter().map(|r| trace(ns, r)).collect::<Vec<_>>();
+    // a region sits directly inside whichever region owns the smallest hole around it
+    let enclosures = regions
         .iter()
-        .enumerate()
-        .flat_map(|(r, row)| {
-            row.iter()
+        .map(|region| {
+            geometries
+                .iter()
                 .enumerate()
-                .map(move |(c, (_, visited))| (r, c, visited))
+                .flat_map(|(i, g)| g.holes.iter().map(move |hole| (i, hole)))
+                .filter(|(_, hole)| contains(hole, region.cells[0]))
+                .max_by_key(|(_, hole)| signed_area(hole))
+                .map(|(i, _)| i)
         })
-        .find(|(_, _, visited)| !**visited)
-    {
-        acc.push(flood_fill(&mut state, r, c));
+        .collect::<Vec<_>>();
+    for (geometry, enclosed_by) in geometries.iter_mut().zip(enclosures) {
+        geometry.enclosed_by = enclosed_by;
     }
-
-    acc
+    geometries
 }
 
-fn sides(set: &HashSet<(usize, usize)>) -> usize {
-    let (r0, rn) = set.iter().map(|(r, _)| *r).minmax().into_option().unwrap();
-    let (c0, cn) = set.iter().map(|(_, c)| *c).minmax().into_option().unwrap();
+fn svg(regions: &[Region], geometries: &[Geometry], (width, height): (usize, usize)) -> String {
+    let mut svg =
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {height}\">\n");
+    for (region, geometry) in regions.iter().zip(geometries) {
+        let d = iter::once(&geometry.outer)
+            .chain(&geometry.holes)
+            .map(|polygon| {
+                format!(
+                    "M {} Z",
+                    polygon.iter().map(|(x, y)| format!("{x} {y}")).join(" L ")
+                )
+            })
+            .join(" ");
+        let hue = region.plant as u32 * 47 % 360;
+        svg += &format!(
+            "  <path d=\"{d}\" fill=\"hsl({hue}, 60%, 70%)\" fill-rule=\"evenodd\" stroke=\"black\" stroke-width=\"0.05\"><title>{}: area {}, sides {}</title></path>\n",
+            region.plant, region.area, region.sides
+        );
+    }
+    svg + "</svg>\n"
+}
 
-    (r0..=(rn + 1))
-        .map(|r| {
-            (c0..=cn)
-                .map(move |c| (r > 0 && set.contains(&(r - 1, c)), set.contains(&(r, c))))
-                .collect::<Vec<_>>()
-                .chunk_by(|a, b| a == b)
-                .filter(|chunk| matches!(chunk, [(a, b), ..] if a != b))
-                .count()
+// outer rings come out counterclockwise and holes clockwise, as RFC 7946 asks
+fn geojson(regions: &[Region], geometries: &[Geometry]) -> String {
+    let features = regions
+        .iter()
+        .zip(geometries)
+        .map(|(region, geometry)| {
+            let rings = iter::once(&geometry.outer)
+                .chain(&geometry.holes)
+                .map(|polygon| {
+                    let ring = polygon
+                        .iter()
+                        .chain(polygon.first())
+                        .map(|(x, y)| format!("[{x}, {y}]"))
+                        .join(", ");
+                    format!("[{ring}]")
+                })
+                .join(", ");
+            let enclosed_by = geometry.enclosed_by.map_or("null".to_string(), |i| i.to_string());
+            format!(
+                "{{\"type\": \"Feature\", \"properties\": {{\"plant\": \"{}\", \"area\": {}, \"perimeter\": {}, \"sides\": {}, \"enclosed_by\": {enclosed_by}}}, \"geometry\": {{\"type\": \"Polygon\", \"coordinates\": [{rings}]}}}}",
+                region.plant, region.area, region.perimeter, region.sides
+            )
         })
-        .sum::<usize>()
-        + (c0..=(cn + 1))
-            .map(|c| {
-                (r0..=rn)
-                    .map(move |r| (c > 0 && set.contains(&(r, c - 1)), set.contains(&(r, c))))
-                    .collect::<Vec<_>>()
-                    .chunk_by(|a, b| a == b)
-                    .filter(|chunk| matches!(chunk, [(a, b), ..] if a != b))
-                    .count()
-            })
-            .sum::<usize>()
+        .join(",\n  ");
+    format!("{{\"type\": \"FeatureCollection\", \"features\": [\n  {features}\n]}}\n")
 }
 
 fn process_part1(ns: &Vec<Vec<char>>) -> usize {
-    process_internal(ns).iter().map(|(a, b, _)| a * b).sum()
+    regions(ns).iter().map(|r| r.area * r.perimeter).sum()
 }
 
 fn process_part2(ns: &Vec<Vec<char>>) -> usize {
-    process_internal(ns)
-        .iter()
-        .map(|(_, _, set)| set.len() * sides(set))
-        .sum()
+    regions(ns).iter().map(|r| r.area * r.sides).sum()
 }
 
 #[cfg(test)]
@@ -137,12 +303,91 @@ mod tests {
         let result = process_part2(&ns);
         assert_eq!(result, 1206);
     }
+
+    #[test]
+    fn test_regions() {
+        let ns = parse("AAAA\nBBCD\nBBCC\nEEEC\nEEEE".lines());
+        let regions = regions(&ns);
+        assert_eq!(
+            regions
+                .iter()
+                .map(|r| (r.plant, r.area, r.perimeter, r.sides, r.bounds))
+                .collect::<Vec<_>>(),
+            [
+                ('A', 4, 10, 4, ((0, 0), (0, 3))),
+                ('B', 4, 8, 4, ((1, 0), (2, 1))),
+                ('C', 4, 10, 8, ((1, 2), (3, 3))),
+                ('D', 1, 4, 4, ((1, 3), (1, 3))),
+                ('E', 7, 12, 6, ((3, 0), (4, 3))),
+            ]
+        );
+        assert_eq!(regions[2].cells, [(1, 2), (2, 2), (2, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn test_geometry() {
+        let ns = parse("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA".lines());
+        let regions = regions(&ns);
+        let geometries = geometries(&ns, &regions);
+        assert_eq!(process_part2(&ns), 368);
+        assert_eq!(geometries[0].outer, [(0, 0), (6, 0), (6, 6), (0, 6)]);
+        // the two B blocks touch at a corner, so they share one hole in A
+        assert_eq!(
+            geometries[0].holes,
+            [[
+                (3, 1),
+                (3, 3),
+                (1, 3),
+                (1, 5),
+                (3, 5),
+                (3, 3),
+                (5, 3),
+                (5, 1)
+            ]]
+        );
+        assert_eq!(geometries[1].outer, [(3, 1), (5, 1), (5, 3), (3, 3)]);
+        assert_eq!(
+            geometries.iter().map(|g| g.enclosed_by).collect::<Vec<_>>(),
+            [None, Some(0), Some(0)]
+        );
+        assert_eq!(
+            geometries.iter().map(Geometry::sides).collect::<Vec<_>>(),
+            regions.iter().map(|r| r.sides).collect::<Vec<_>>()
+        );
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input.lines());
 
+    let command = env::args().nth(1);
+    if let Some("regions" | "svg" | "geojson") = command.as_deref() {
+        let regions = regions(&ns);
+        let geometries = geometries(&ns, &regions);
+        match command.as_deref() {
+            Some("svg") => print!("{}", svg(&regions, &geometries, (ns[0].len(), ns.len()))),
+            Some("geojson") => print!("{}", geojson(&regions, &geometries)),
+            _ => {
+                for (i, (r, g)) in regions.iter().zip(&geometries).enumerate() {
+                    println!(
+                        "{i} {}: area {}, perimeter {}, sides {}, {} holes, {:?} to {:?}{}",
+                        r.plant,
+                        r.area,
+                        r.perimeter,
+                        g.sides(),
+                        g.holes.len(),
+                        r.bounds.0,
+                        r.bounds.1,
+                        g.enclosed_by
+                            .map_or(String::new(), |e| format!(", inside {e}"))
+                    );
+                }
+            }
+        }
+        return;
+    }
+
     let result_part1 = process_part1(&ns);
     println!("{}", result_part1);
 
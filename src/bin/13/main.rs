@@ -1,58 +1,155 @@
+use std::env;
+
 use itertools::Itertools;
-use num_integer::{lcm, Integer, Roots};
+use num_integer::Integer;
 use regex::Regex;
 
-type Game = ((usize, usize), (usize, usize), (usize, usize));
+type Vector = (i128, i128);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Machine {
+    a: Vector,
+    b: Vector,
+    prize: Vector,
+}
+
+#[derive(Debug, PartialEq)]
+struct Presses {
+    a: i128,
+    b: i128,
+    cost: i128,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Config {
+    costs: (i128, i128),
+    limit: Option<i128>,
+    offset: i128,
+}
+
+const PART1: Config = Config {
+    costs: (3, 1),
+    limit: Some(100),
+    offset: 0,
+};
+
+const PART2: Config = Config {
+    costs: (3, 1),
+    limit: None,
+    offset: 10000000000000,
+};
 
-fn parse(input: &str) -> Vec<Game> {
+fn parse(input: &str) -> Vec<Machine> {
     let regex = Regex::new(r"(\d+).+?(\d+)").unwrap();
 
     regex
         .captures_iter(input)
         .map(|cap| cap.extract())
         .map(|(_, vals)| vals.map(|v| v.parse().unwrap()))
-        .map(|[a, b]| (a, b))
+        .map(|[x, y]| (x, y))
         .tuples()
+        .map(|(a, b, prize)| Machine { a, b, prize })
         .collect()
 }
 
-fn hypotenuse(x: &usize, y: &usize) -> usize {
-    (x.pow(2) + y.pow(2)).sqrt()
+fn cross(u: Vector, v: Vector) -> i128 {
+    u.0 * v.1 - u.1 * v.0
 }
 
-fn solve_for(&((ax, ay), (bx, by), (x, y)): &Game) -> Option<(usize, usize)> {
-    let alcm = lcm(ax, ay);
+impl Config {
+    fn presses(&self, a: i128, b: i128) -> Option<Presses> {
+        let allowed = |n: i128| n >= 0 && self.limit.is_none_or(|limit| n <= limit);
+        (allowed(a) && allowed(b)).then(|| Presses {
+            a,
+            b,
+            cost: a * self.costs.0 + b * self.costs.1,
+        })
+    }
+}
 
-    let (nb, remb) = ((x * alcm / ax) as i64 - (y * alcm / ay) as i64)
-        .div_rem(&((bx * alcm / ax) as i64 - (by * alcm / ay) as i64));
-    if remb == 0 {
-        let (na, rema) = (x - nb as usize * bx).div_rem(&ax);
-        if rema == 0 {
-            Some((na, nb as usize))
+// cheapest allowed n, m with n * a + m * b = target along an axis where a or b is non-zero
+fn cheapest(a: i128, b: i128, target: i128, config: &Config) -> Option<Presses> {
+    let solution = a.extended_gcd(&b);
+    let g = solution.gcd;
+    if target % g != 0 {
+        return None;
+    }
+    let (n, m) = (solution.x * (target / g), solution.y * (target / g));
+    let (step_n, step_m) = (b / g, a / g);
+
+    // every solution is n + k * step_n, m - k * step_m, so k is bounded by both staying in range
+    let floor = |x: i128, step: i128| (step != 0).then(|| Integer::div_floor(&x, &step));
+    let lower = [
+        floor(n, step_n).map(|k| -k),
+        config
+            .limit
+            .and_then(|limit| floor(limit - m, step_m))
+            .map(|k| -k),
+    ]
+    .into_iter()
+    .flatten()
+    .max();
+    let upper = [
+        floor(m, step_m),
+        config.limit.and_then(|limit| floor(limit - n, step_n)),
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+    if let (Some(lower), Some(upper)) = (lower, upper) {
+        if lower > upper {
+            return None;
+        }
+    }
+
+    // the cost is linear in k, so the cheapest solution sits at one of the bounds
+    let slope = step_n * config.costs.0 - step_m * config.costs.1;
+    let k = if slope > 0 { lower } else { upper }
+        .or(lower)
+        .or(upper)
+        .unwrap();
+    config.presses(n + k * step_n, m - k * step_m)
+}
+
+fn solve(machine: &Machine, config: &Config) -> Option<Presses> {
+    let Machine { a, b, prize } = *machine;
+    let prize = (prize.0 + config.offset, prize.1 + config.offset);
+    let det = cross(a, b);
+    if det != 0 {
+        let (n, rem_n) = cross(prize, b).div_rem(&det);
+        let (m, rem_m) = cross(a, prize).div_rem(&det);
+        return if rem_n == 0 && rem_m == 0 {
+            config.presses(n, m)
         } else {
             None
-        }
+        };
+    }
+
+    // buttons that don't move the claw only win a prize already under it
+    if a == (0, 0) && b == (0, 0) {
+        return if prize == (0, 0) {
+            config.presses(0, 0)
+        } else {
+            None
+        };
+    }
+
+    // collinear buttons can only reach prizes on their shared line
+    let direction = if a != (0, 0) { a } else { b };
+    if cross(direction, prize) != 0 {
+        return None;
+    }
+    if a.0 != 0 || b.0 != 0 {
+        cheapest(a.0, b.0, prize.0, config)
     } else {
-        None
+        cheapest(a.1, b.1, prize.1, config)
     }
 }
 
-fn process(ns: &Vec<Game>) -> usize {
+fn process(ns: &[Machine], config: &Config) -> i128 {
     ns.iter()
-        .map(|((ax, ay), (bx, by), (x, y))| {
-            let a_coef = hypotenuse(ax, ay) / 3;
-            let b_coef = hypotenuse(bx, by);
-
-            if a_coef >= b_coef {
-                solve_for(&((*ax, *ay), (*bx, *by), (*x, *y)))
-                    .map(|(a, b)| a * 3 + b)
-                    .unwrap_or_default()
-            } else {
-                solve_for(&((*bx, *by), (*ax, *ay), (*x, *y)))
-                    .map(|(b, a)| a * 3 + b)
-                    .unwrap_or_default()
-            }
-        })
+        .filter_map(|machine| solve(machine, config))
+        .map(|p| p.cost)
         .sum()
 }
 
@@ -65,22 +162,123 @@ mod tests {
     #[test]
     fn test_part1() {
         let ns = parse(INPUT);
-        let result = process(&ns);
+        let result = process(&ns, &PART1);
         assert_eq!(result, 480);
     }
+
+    #[test]
+    fn test_part2() {
+        let ns = parse(INPUT);
+        let result = process(&ns, &PART2);
+        assert_eq!(result, 875318608908);
+        assert_eq!(
+            ns.iter()
+                .map(|m| solve(m, &PART2).is_some())
+                .collect::<Vec<_>>(),
+            [false, true, false, true]
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let ns = parse(INPUT);
+        assert_eq!(
+            solve(&ns[0], &PART1),
+            Some(Presses {
+                a: 80,
+                b: 40,
+                cost: 280
+            })
+        );
+        let capped = Config {
+            limit: Some(50),
+            ..PART1
+        };
+        assert_eq!(solve(&ns[0], &capped), None);
+
+        // collinear buttons: the cheapest mix depends on the costs and the cap
+        let machine = Machine {
+            a: (2, 4),
+            b: (3, 6),
+            prize: (12, 24),
+        };
+        let presses = |config: &Config| solve(&machine, config).map(|p| (p.a, p.b, p.cost));
+        assert_eq!(presses(&PART1), Some((0, 4, 4)));
+        let reversed = Config {
+            costs: (1, 3),
+            ..PART1
+        };
+        assert_eq!(presses(&reversed), Some((6, 0, 6)));
+        assert_eq!(
+            presses(&Config {
+                limit: Some(5),
+                ..reversed
+            }),
+            Some((3, 2, 9))
+        );
+        assert_eq!(
+            presses(&Config {
+                limit: Some(3),
+                ..PART1
+            }),
+            Some((3, 2, 11))
+        );
+        assert_eq!(
+            solve(
+                &Machine {
+                    prize: (3, 5),
+                    ..machine
+                },
+                &PART1
+            ),
+            None
+        );
+
+        let stuck = Machine {
+            a: (0, 0),
+            b: (0, 0),
+            prize: (5, 0),
+        };
+        assert_eq!(solve(&stuck, &PART1), None);
+        assert_eq!(
+            solve(
+                &Machine {
+                    prize: (0, 0),
+                    ..stuck
+                },
+                &PART1
+            ),
+            Some(Presses {
+                a: 0,
+                b: 0,
+                cost: 0
+            })
+        );
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input);
 
-    let result_part1 = process(&ns);
+    if env::args().nth(1).as_deref() == Some("machines") {
+        let config = if env::args().nth(2).as_deref() == Some("part2") {
+            PART2
+        } else {
+            PART1
+        };
+        for (i, machine) in ns.iter().enumerate() {
+            match solve(machine, &config) {
+                Some(p) => println!("{i}: {} A, {} B, {} tokens", p.a, p.b, p.cost),
+                None => println!("{i}: unreachable"),
+            }
+        }
+        return;
+    }
+
+    let result_part1 = process(&ns, &PART1);
     println!("{}", result_part1);
 
-    let result_part2 = process(
-        &ns.iter()
-            .map(|(a, b, (x, y))| (*a, *b, (x + 10000000000000, y + 10000000000000)))
-            .collect(),
-    );
+    let result_part2 = process(&ns, &PART2);
     println!("{}", result_part2);
 }
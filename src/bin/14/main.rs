@@ -1,32 +1,103 @@
-use std::{
-    collections::BTreeMap,
-    ops::{Add, ControlFlow, Rem},
-    str::Lines,
-};
+use std::{env, ops::Range, str::Lines};
 
 use itertools::Itertools;
+use num_integer::Integer;
 use regex::Regex;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-struct Point(i32, i32);
+struct Point(i64, i64);
 
-impl Add for Point {
-    type Output = Self;
+#[derive(Debug, Clone, Copy)]
+struct Robot {
+    position: Point,
+    velocity: Point,
+}
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
+impl Robot {
+    // reduce before multiplying so any t fits
+    fn at(&self, t: i64, size: Point) -> Point {
+        let axis = |p: i64, v: i64, n: i64| (p + v.rem_euclid(n) * t.rem_euclid(n)).rem_euclid(n);
+        Point(
+            axis(self.position.0, self.velocity.0, size.0),
+            axis(self.position.1, self.velocity.1, size.1),
+        )
     }
 }
 
-impl Rem for Point {
-    type Output = Self;
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Strategy {
+    Variance,
+    Entropy,
+}
 
-    fn rem(self, rhs: Self) -> Self::Output {
-        Self(self.0 % rhs.0, self.1 % rhs.1)
+impl Strategy {
+    // lower means more clustered along one axis
+    fn score(self, values: &[i64], range: i64) -> f64 {
+        let n = values.len() as f64;
+        match self {
+            Strategy::Variance => {
+                let mean = values.iter().sum::<i64>() as f64 / n;
+                values
+                    .iter()
+                    .map(|&v| (v as f64 - mean).powi(2))
+                    .sum::<f64>()
+                    / n
+            }
+            Strategy::Entropy => {
+                let mut counts = vec![0; range as usize];
+                for &v in values {
+                    counts[v as usize] += 1;
+                }
+                counts
+                    .iter()
+                    .filter(|&&c| c > 0)
+                    .map(|&c| -(c as f64 / n) * (c as f64 / n).ln())
+                    .sum()
+            }
+        }
     }
 }
 
-fn parse(iter: Lines) -> Vec<(Point, Point)> {
+#[derive(Debug, Clone, Copy)]
+struct Detector {
+    strategy: Strategy,
+    // how many standard deviations below the average the best second on each axis must score
+    threshold: Option<f64>,
+}
+
+const DETECTOR: Detector = Detector {
+    strategy: Strategy::Variance,
+    threshold: Some(4.0),
+};
+
+#[derive(Debug, PartialEq)]
+struct Frame {
+    t: i64,
+    occupied: Vec<Vec<bool>>,
+}
+
+impl Frame {
+    fn new(ns: &[Robot], t: i64, size: Point) -> Self {
+        let mut occupied = vec![vec![false; size.0 as usize]; size.1 as usize];
+        for Point(x, y) in positions(ns, t, size) {
+            occupied[y as usize][x as usize] = true;
+        }
+        Frame { t, occupied }
+    }
+
+    fn render(&self) -> String {
+        self.occupied
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&o| if o { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
+
+fn parse(iter: Lines) -> Vec<Robot> {
     let regex = Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
     iter.flat_map(|s| {
         regex
@@ -38,76 +109,89 @@ fn parse(iter: Lines) -> Vec<(Point, Point)> {
             .tuples()
             .collect::<Vec<_>>()
     })
-    .map(|(px, py, vx, vy)| (Point(px, py), Point(vx, vy)))
+    .map(|(px, py, vx, vy)| Robot {
+        position: Point(px, py),
+        velocity: Point(vx, vy),
+    })
     .collect()
 }
 
-fn process_part1(ns: &Vec<(Point, Point)>, size: Point) -> usize {
-    (0..100)
-        .fold(ns.iter().copied().collect::<Vec<_>>(), |acc, _| {
-            acc.iter()
-                .map(|&(p, v)| ((p + v + size) % size, v))
-                .collect()
-        })
-        .iter()
-        .fold(
-            BTreeMap::<(bool, bool), usize>::new(),
-            |mut acc, &(Point(x, y), _)| {
-                if x == size.0 / 2 || y == size.1 / 2 {
-                    acc
-                } else {
-                    acc.entry((x > size.0 / 2, y > size.1 / 2))
-                        .and_modify(|n| *n += 1)
-                        .or_insert(1);
-                    acc
-                }
-            },
-        )
+fn positions(ns: &[Robot], t: i64, size: Point) -> Vec<Point> {
+    ns.iter().map(|r| r.at(t, size)).collect()
+}
+
+fn count_in(ns: &[Robot], t: i64, size: Point, xs: Range<i64>, ys: Range<i64>) -> usize {
+    positions(ns, t, size)
         .iter()
-        .map(|(_, n)| n)
-        .product()
+        .filter(|Point(x, y)| xs.contains(x) && ys.contains(y))
+        .count()
 }
 
-fn process_part2(ns: &Vec<(Point, Point)>, size: Point) -> Option<i32> {
-    let mut result = None;
+// top left, top right, bottom left, bottom right, leaving out the middle row and column
+fn quadrants(ns: &[Robot], t: i64, size: Point) -> [usize; 4] {
+    let Point(width, height) = size;
+    let (left, right) = (0..width / 2, (width + 1) / 2..width);
+    let (top, bottom) = (0..height / 2, (height + 1) / 2..height);
+    [
+        count_in(ns, t, size, left.clone(), top.clone()),
+        count_in(ns, t, size, right.clone(), top),
+        count_in(ns, t, size, left, bottom.clone()),
+        count_in(ns, t, size, right, bottom),
+    ]
+}
 
-    (1..).try_fold(ns.iter().copied().collect::<Vec<_>>(), |acc, i| {
-        let r: Vec<_> = acc
-            .iter()
-            .map(|&(p, v)| ((p + v + size) % size, v))
-            .collect();
+fn safety_factor(ns: &[Robot], t: i64, size: Point) -> usize {
+    quadrants(ns, t, size).iter().product()
+}
 
-        if (0..size.1).any(|row| {
-            (0..size.0).collect::<Vec<_>>().windows(30).any(|w| {
-                w.iter()
-                    .all(|col| r.iter().any(|(p, _)| p == &Point(*col, row)))
-            })
-        }) {
-            (0..size.1)
-                .inspect(|row| {
-                    println!(
-                        "{}",
-                        (0..size.0)
-                            .map(|col| {
-                                if r.iter().any(|(p, _)| *p == Point(col, *row)) {
-                                    '#'
-                                } else {
-                                    '.'
-                                }
-                            })
-                            .collect::<String>()
-                    );
-                })
-                .for_each(drop);
-
-            result = Some(i);
-            return ControlFlow::Break(r);
-        }
+fn process_part1(ns: &[Robot], size: Point) -> usize {
+    safety_factor(ns, 100, size)
+}
 
-        ControlFlow::Continue(r)
-    });
+// x repeats every width seconds, so the most structured second on that axis lies within one period
+fn best_second(ns: &[Robot], size: Point, axis: usize, detector: &Detector) -> Option<i64> {
+    let period = [size.0, size.1][axis];
+    let scores = (0..period)
+        .map(|t| {
+            let values = positions(ns, t, size)
+                .iter()
+                .map(|p| [p.0, p.1][axis])
+                .collect::<Vec<_>>();
+            detector.strategy.score(&values, period)
+        })
+        .collect::<Vec<_>>();
 
-    result
+    let (t, best) = scores
+        .iter()
+        .copied()
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    let average = scores.iter().sum::<f64>() / period as f64;
+    let deviation =
+        (scores.iter().map(|s| (s - average).powi(2)).sum::<f64>() / period as f64).sqrt();
+    match detector.threshold {
+        Some(threshold) if best > average - threshold * deviation => None,
+        _ => Some(t as i64),
+    }
+}
+
+// the smallest t with t = a mod m and t = b mod n
+fn crt((a, m): (i64, i64), (b, n): (i64, i64)) -> Option<i64> {
+    let solution = m.extended_gcd(&n);
+    let g = solution.gcd;
+    if (b - a) % g != 0 {
+        return None;
+    }
+    let lcm = m / g * n;
+    let k = ((b - a) / g * solution.x).rem_euclid(n / g);
+    Some((a + m * k).rem_euclid(lcm))
+}
+
+fn process_part2(ns: &[Robot], size: Point, detector: &Detector) -> Option<Frame> {
+    let x = best_second(ns, size, 0, detector)?;
+    let y = best_second(ns, size, 1, detector)?;
+    let t = crt((x, size.0), (y, size.1))?;
+    Some(Frame::new(ns, t, size))
 }
 
 #[cfg(test)]
@@ -122,15 +206,103 @@ mod tests {
         let result = process_part1(&ns, Point(11, 7));
         assert_eq!(result, 12);
     }
+
+    #[test]
+    fn test_positions() {
+        let ns = parse(INPUT.lines());
+        let size = Point(11, 7);
+        let robot = ns[10];
+        assert_eq!(robot.velocity, Point(2, -3));
+        assert_eq!(
+            (0..=5).map(|t| robot.at(t, size)).collect::<Vec<_>>(),
+            [
+                Point(2, 4),
+                Point(4, 1),
+                Point(6, 5),
+                Point(8, 2),
+                Point(10, 6),
+                Point(1, 3)
+            ]
+        );
+        assert_eq!(quadrants(&ns, 100, size), [1, 3, 4, 1]);
+        assert_eq!(count_in(&ns, 100, size, 0..11, 0..7), ns.len());
+        assert_eq!(count_in(&ns, 100, size, 6..7, 0..1), 2);
+
+        // every robot is back where it started after width * height seconds
+        let t = 1_000_000_000_000;
+        assert_eq!(positions(&ns, t, size), positions(&ns, t % 77, size));
+        assert_eq!(safety_factor(&ns, t + 100 - t % 77, size), 12);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(crt((2, 11), (5, 7)), Some(68));
+        assert_eq!(crt((1, 4), (3, 6)), Some(9));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+
+        // hide a 3x2 block that forms at second 30 among the example's robots
+        let size = Point(11, 7);
+        let mut ns = parse(INPUT.lines());
+        for i in 0..15 {
+            let velocity = Point(i * 7 % 11 - 5, i * 5 % 7 - 3);
+            let target = Point(4 + i % 3, 2 + i % 2);
+            ns.push(Robot {
+                position: Point(
+                    (target.0 - velocity.0 * 30).rem_euclid(size.0),
+                    (target.1 - velocity.1 * 30).rem_euclid(size.1),
+                ),
+                velocity,
+            });
+        }
+
+        for strategy in [Strategy::Variance, Strategy::Entropy] {
+            let detector = Detector {
+                strategy,
+                threshold: Some(1.5),
+            };
+            let frame = process_part2(&ns, size, &detector).unwrap();
+            assert_eq!(frame.t, 30);
+            assert!(frame.render().lines().nth(2).unwrap().contains("###"));
+        }
+        // a single second out of 11 can never stand 4 deviations clear
+        assert_eq!(process_part2(&ns, size, &DETECTOR), None);
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let ns = parse(input.lines());
 
+    if env::args().nth(1).as_deref() == Some("safety") {
+        let t = env::args().nth(2).map_or(100, |t| t.parse().unwrap());
+        let size = Point(101, 103);
+        println!(
+            "quadrants {:?}, safety factor {}",
+            quadrants(&ns, t, size),
+            safety_factor(&ns, t, size)
+        );
+        return;
+    }
+
+    if env::args().nth(1).as_deref() == Some("detect") {
+        let detector = Detector {
+            strategy: match env::args().nth(2).as_deref() {
+                Some("entropy") => Strategy::Entropy,
+                _ => Strategy::Variance,
+            },
+            threshold: env::args().nth(3).map(|t| t.parse().unwrap()),
+        };
+        match process_part2(&ns, Point(101, 103), &detector) {
+            Some(frame) => println!("{}\n{}", frame.render(), frame.t),
+            None => println!("no structured second found"),
+        }
+        return;
+    }
+
     let result_part1 = process_part1(&ns, Point(101, 103));
     println!("{}", result_part1);
 
-    let result_part2 = process_part2(&ns, Point(101, 103));
-    println!("{}", result_part2.unwrap());
+    let result_part2 = process_part2(&ns, Point(101, 103), &DETECTOR).unwrap();
+    println!("{}", result_part2.render());
+    println!("{}", result_part2.t);
 }
@@ -1,4 +1,4 @@
-use std::{ops::Add, str::Lines};
+use std::{collections::HashSet, env, ops::Add, str::Lines};
 
 use itertools::Itertools;
 
@@ -9,14 +9,6 @@ enum Cell {
     Wall,
 }
 
-#[derive(PartialEq, Clone, Copy)]
-enum Cell2 {
-    None,
-    BoxLeft,
-    BoxRight,
-    Wall,
-}
-
 #[derive(PartialEq, Clone, Copy, Debug)]
 enum Direction {
     Up,
@@ -25,22 +17,256 @@ enum Direction {
     Right,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Direction {
+    fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
 struct Point(usize, usize);
 
 impl Add<Direction> for Point {
-    type Output = Self;
+    type Output = Option<Self>;
 
     fn add(self, rhs: Direction) -> Self::Output {
         match rhs {
-            Direction::Up => Self(self.0 - 1, self.1),
-            Direction::Down => Self(self.0 + 1, self.1),
-            Direction::Left => Self(self.0, self.1 - 1),
-            Direction::Right => Self(self.0, self.1 + 1),
+            Direction::Up => Some(Self(self.0.checked_sub(1)?, self.1)),
+            Direction::Down => Some(Self(self.0 + 1, self.1)),
+            Direction::Left => Some(Self(self.0, self.1.checked_sub(1)?)),
+            Direction::Right => Some(Self(self.0, self.1 + 1)),
         }
     }
 }
 
+// every box is `scale` cells wide and tracked by the cell at its left edge
+struct Warehouse {
+    scale: usize,
+    walls: Vec<Vec<bool>>,
+    boxes: Vec<Point>,
+    occupancy: Vec<Vec<Option<usize>>>,
+    robot: Point,
+}
+
+impl Warehouse {
+    fn new(map: &[Vec<Cell>], &Point(row, col): &Point, scale: usize) -> Self {
+        let width = map.first().map_or(0, Vec::len) * scale;
+        let mut warehouse = Warehouse {
+            scale,
+            walls: map
+                .iter()
+                .map(|r| {
+                    r.iter()
+                        .flat_map(|&c| vec![c == Cell::Wall; scale])
+                        .collect()
+                })
+                .collect(),
+            boxes: vec![],
+            occupancy: vec![vec![None; width]; map.len()],
+            robot: Point(row, col * scale),
+        };
+
+        for (r, cells) in map.iter().enumerate() {
+            for (c, &cell) in cells.iter().enumerate() {
+                if cell == Cell::Box {
+                    warehouse.place(warehouse.boxes.len(), Point(r, c * scale));
+                    warehouse.boxes.push(Point(r, c * scale));
+                }
+            }
+        }
+        warehouse
+    }
+
+    fn cells(&self, Point(row, col): Point) -> impl Iterator<Item = Point> {
+        (col..col + self.scale).map(move |c| Point(row, c))
+    }
+
+    fn place(&mut self, id: usize, at: Point) {
+        for Point(r, c) in self.cells(at) {
+            self.occupancy[r][c] = Some(id);
+        }
+    }
+
+    fn wall(&self, point: Option<Point>) -> bool {
+        point.is_none_or(|Point(r, c)| self.walls.get(r).and_then(|row| row.get(c)) != Some(&false))
+    }
+
+    fn occupant(&self, Point(r, c): Point) -> Option<usize> {
+        self.occupancy.get(r)?.get(c).copied().flatten()
+    }
+
+    // every box the push would move, or None if any of them is blocked by a wall
+    fn affected(&self, direction: Direction) -> Option<Vec<usize>> {
+        let mut moved = vec![];
+        let mut seen = HashSet::new();
+        let mut frontier = vec![self.robot + direction];
+        while let Some(next) = frontier.pop() {
+            if self.wall(next) {
+                return None;
+            }
+            let Some(id) = self.occupant(next.unwrap()) else {
+                continue;
+            };
+            if seen.insert(id) {
+                moved.push(id);
+                frontier.extend(
+                    self.cells(self.boxes[id])
+                        .map(|p| p + direction)
+                        .filter(|&p| p.is_none_or(|p| self.occupant(p) != Some(id))),
+                );
+            }
+        }
+        Some(moved)
+    }
+
+    fn shift(&mut self, moved: &[usize], direction: Direction) {
+        for &id in moved {
+            for Point(r, c) in self.cells(self.boxes[id]) {
+                self.occupancy[r][c] = None;
+            }
+        }
+        for &id in moved {
+            self.boxes[id] = (self.boxes[id] + direction).unwrap();
+            self.place(id, self.boxes[id]);
+        }
+        self.robot = (self.robot + direction).unwrap();
+    }
+
+    // moves the robot and everything it pushes, or nothing at all
+    fn step(&mut self, direction: Direction) -> Option<Vec<usize>> {
+        let moved = self.affected(direction)?;
+        self.shift(&moved, direction);
+        Some(moved)
+    }
+
+    fn undo(&mut self, step: &Move) {
+        if step.success {
+            self.shift(&step.boxes, step.direction.opposite());
+        }
+    }
+
+    // wider boxes than the puzzle's fill the space between their brackets with '='
+    fn render(&self) -> String {
+        let mut rows = self
+            .walls
+            .iter()
+            .map(|r| {
+                r.iter()
+                    .map(|&w| if w { '#' } else { '.' })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for &Point(r, c) in &self.boxes {
+            if self.scale == 1 {
+                rows[r][c] = 'O';
+            } else {
+                rows[r][c..c + self.scale].fill('=');
+                rows[r][c] = '[';
+                rows[r][c + self.scale - 1] = ']';
+            }
+        }
+        rows[self.robot.0][self.robot.1] = '@';
+        rows.iter().map(|r| r.iter().collect::<String>()).join("\n")
+    }
+
+    // reads a rendered map back, taking the box width from the first box found
+    fn parse(input: &str) -> Self {
+        let scale = input
+            .lines()
+            .find_map(|s| {
+                if s.contains('O') {
+                    Some(1)
+                } else {
+                    let start = s.find('[')?;
+                    Some(s[start..].find(']')? + 1)
+                }
+            })
+            .unwrap_or(1);
+
+        let mut warehouse = Warehouse {
+            scale,
+            walls: input
+                .lines()
+                .map(|s| s.chars().map(|c| c == '#').collect())
+                .collect(),
+            boxes: vec![],
+            occupancy: input
+                .lines()
+                .map(|s| vec![None; s.chars().count()])
+                .collect(),
+            robot: Point(0, 0),
+        };
+        for (r, s) in input.lines().enumerate() {
+            for (c, glyph) in s.chars().enumerate() {
+                match glyph {
+                    '@' => warehouse.robot = Point(r, c),
+                    'O' | '[' => {
+                        warehouse.place(warehouse.boxes.len(), Point(r, c));
+                        warehouse.boxes.push(Point(r, c));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        warehouse
+    }
+
+    fn gps(&self) -> usize {
+        self.boxes.iter().map(|Point(r, c)| r * 100 + c).sum()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Move {
+    direction: Direction,
+    success: bool,
+    boxes: Vec<usize>,
+}
+
+struct Replay {
+    warehouse: Warehouse,
+    moves: Vec<Direction>,
+    log: Vec<Move>,
+}
+
+impl Replay {
+    fn new(warehouse: Warehouse, moves: &[Direction]) -> Self {
+        Replay {
+            warehouse,
+            moves: moves.to_vec(),
+            log: vec![],
+        }
+    }
+
+    fn forward(&mut self) -> Option<&Move> {
+        let &direction = self.moves.get(self.log.len())?;
+        let moved = self.warehouse.step(direction);
+        self.log.push(Move {
+            direction,
+            success: moved.is_some(),
+            boxes: moved.unwrap_or_default(),
+        });
+        self.log.last()
+    }
+
+    fn back(&mut self) -> Option<Move> {
+        let step = self.log.pop()?;
+        self.warehouse.undo(&step);
+        Some(step)
+    }
+
+    // leaves the warehouse as it was after the first n moves
+    fn seek(&mut self, n: usize) {
+        while self.log.len() > n && self.back().is_some() {}
+        while self.log.len() < n && self.forward().is_some() {}
+    }
+}
+
 fn parse(iter: Lines) -> (Vec<Vec<Cell>>, Point, Vec<Direction>) {
     let mut map = vec![];
     let mut position = None;
@@ -81,122 +307,12 @@ fn parse(iter: Lines) -> (Vec<Vec<Cell>>, Point, Vec<Direction>) {
     (map, position.unwrap(), moves)
 }
 
-fn map2(map: &Vec<Vec<Cell>>) -> Vec<Vec<Cell2>> {
-    map.iter()
-        .map(|r| {
-            r.iter()
-                .flat_map(|c| match c {
-                    Cell::None => [Cell2::None; 2],
-                    Cell::Box => [Cell2::BoxLeft, Cell2::BoxRight],
-                    Cell::Wall => [Cell2::Wall; 2],
-                })
-                .collect()
-        })
-        .collect()
-}
-
-fn push(map: &mut Vec<Vec<Cell2>>, from: &Point, direction: &Direction, tail: bool) -> bool {
-    let nextpoint = *from + *direction;
-    let nextcell = map[nextpoint.0][nextpoint.1];
-    let result = match nextcell {
-        Cell2::None => true,
-        Cell2::Wall => false,
-        Cell2::BoxLeft if *direction == Direction::Up || *direction == Direction::Down => {
-            push(map, &nextpoint, direction, false)
-                && (tail || push(map, &(*from + Direction::Right), direction, true))
-        }
-        Cell2::BoxRight if *direction == Direction::Up || *direction == Direction::Down => {
-            push(map, &nextpoint, direction, false)
-                && (tail || push(map, &(*from + Direction::Left), direction, true))
-        }
-        _ => push(map, &nextpoint, direction, false),
-    };
-
-    if result && (nextcell == Cell2::BoxLeft || nextcell == Cell2::BoxRight) {
-        map[nextpoint.0][nextpoint.1] = Cell2::None;
-        let pushed_into = nextpoint + *direction;
-        map[pushed_into.0][pushed_into.1] = nextcell;
+fn process(map: &[Vec<Cell>], position: &Point, moves: &[Direction], scale: usize) -> usize {
+    let mut warehouse = Warehouse::new(map, position, scale);
+    for &direction in moves {
+        warehouse.step(direction);
     }
-
-    result
-}
-
-fn process_part1(map: &Vec<Vec<Cell>>, position: &Point, moves: &Vec<Direction>) -> usize {
-    moves
-        .iter()
-        .fold((map.clone(), *position), |(prevmap, prevpos), direction| {
-            let Point(r, c) = prevpos;
-            let slice = match direction {
-                Direction::Up => (0..r).rev().map(|rr| prevmap[rr][c]).collect::<Vec<_>>(),
-                Direction::Down => (r + 1..prevmap.len())
-                    .map(|rr| prevmap[rr][c])
-                    .collect::<Vec<_>>(),
-                Direction::Left => (0..c).rev().map(|cc| prevmap[r][cc]).collect::<Vec<_>>(),
-                Direction::Right => (c + 1..prevmap[0].len())
-                    .map(|cc| prevmap[r][cc])
-                    .collect::<Vec<_>>(),
-            };
-            let chunks = slice.iter().chunk_by(|v| **v);
-            let mut chunk_iter = chunks.into_iter();
-
-            let chunk_a = chunk_iter.next().unwrap();
-            let chunk_b = chunk_iter.next();
-
-            match chunk_a {
-                (Cell::None, _) => (prevmap, prevpos + *direction),
-                (Cell::Wall, _) => (prevmap, prevpos),
-                (Cell::Box, boxes) => match chunk_b {
-                    None | Some((Cell::Wall, _)) => (prevmap, prevpos),
-                    Some((Cell::None, _)) => {
-                        let mut nextmap = prevmap.clone();
-                        let nextpos = prevpos + *direction;
-                        let nextbox = boxes.fold(nextpos, |p, _| p + *direction);
-                        nextmap[nextpos.0][nextpos.1] = Cell::None;
-                        nextmap[nextbox.0][nextbox.1] = Cell::Box;
-                        (nextmap, nextpos)
-                    }
-                    _ => panic!(),
-                },
-            }
-        })
-        .0
-        .iter()
-        .enumerate()
-        .flat_map(|(r, row)| {
-            row.iter()
-                .enumerate()
-                .map(move |(c, cell)| if *cell == Cell::Box { r * 100 + c } else { 0 })
-        })
-        .sum()
-}
-
-fn process_part2(map: &Vec<Vec<Cell>>, &Point(row, col): &Point, moves: &Vec<Direction>) -> usize {
-    moves
-        .iter()
-        .fold(
-            (map2(map), Point(row, col * 2)),
-            |(prevmap, prevpos), direction| {
-                let mut nextmap = prevmap.clone();
-                if push(&mut nextmap, &prevpos, direction, false) {
-                    (nextmap, prevpos + *direction)
-                } else {
-                    (prevmap, prevpos)
-                }
-            },
-        )
-        .0
-        .iter()
-        .enumerate()
-        .flat_map(|(r, row)| {
-            row.iter().enumerate().map(move |(c, cell)| {
-                if *cell == Cell2::BoxLeft {
-                    r * 100 + c
-                } else {
-                    0
-                }
-            })
-        })
-        .sum()
+    warehouse.gps()
 }
 
 #[cfg(test)]
@@ -208,25 +324,122 @@ mod tests {
     #[test]
     fn test_part1() {
         let (map, position, moves) = parse(INPUT.lines());
-        let result = process_part1(&map, &position, &moves);
+        let result = process(&map, &position, &moves, 1);
         assert_eq!(result, 10092);
     }
 
     #[test]
     fn test_part2() {
         let (map, position, moves) = parse(INPUT.lines());
-        let result = process_part2(&map, &position, &moves);
+        let result = process(&map, &position, &moves, 2);
         assert_eq!(result, 9021);
     }
+
+    #[test]
+    fn test_scale() {
+        let (map, position, _) = parse("#####\n#.O.#\n#.O.#\n#.@.#\n#####\n\n^".lines());
+        let mut warehouse = Warehouse::new(&map, &position, 3);
+        assert_eq!(warehouse.step(Direction::Up), None);
+        assert_eq!(warehouse.boxes, [Point(1, 6), Point(2, 6)]);
+        assert_eq!(warehouse.step(Direction::Left), Some(vec![]));
+        assert_eq!(warehouse.step(Direction::Up), Some(vec![]));
+        assert_eq!(warehouse.robot, Point(2, 5));
+        assert_eq!(warehouse.step(Direction::Right), Some(vec![1]));
+        assert_eq!(warehouse.gps(), 100 + 6 + 200 + 7);
+
+        // no walls around the edge: the robot stops at the border instead of wrapping
+        let (map, position, moves) = parse("@O.\n\n<^>>>>".lines());
+        let mut warehouse = Warehouse::new(&map, &position, 1);
+        let moved = moves
+            .iter()
+            .map(|&d| warehouse.step(d).is_some())
+            .collect::<Vec<_>>();
+        assert_eq!(moved, [false, false, true, false, false, false]);
+        assert_eq!(warehouse.gps(), 2);
+    }
+
+    #[test]
+    fn test_replay() {
+        let input = "#######\n#...#.#\n#.....#\n#..OO@#\n#..O..#\n#.....#\n#######\n\n<vv<<^^<<^^";
+        let (map, position, moves) = parse(input.lines());
+        let mut replay = Replay::new(Warehouse::new(&map, &position, 2), &moves);
+        let start = replay.warehouse.render();
+
+        assert_eq!(
+            replay.forward(),
+            Some(&Move {
+                direction: Direction::Left,
+                success: true,
+                boxes: vec![1, 0]
+            })
+        );
+        assert_eq!(
+            replay.warehouse.render(),
+            "##############\n##......##..##\n##..........##\n##...[][]@..##\n##....[]....##\n##..........##\n##############"
+        );
+
+        replay.seek(moves.len());
+        assert_eq!(
+            replay
+                .log
+                .iter()
+                .positions(|m| !m.success)
+                .collect::<Vec<_>>(),
+            [6]
+        );
+        assert!(replay.log[6].boxes.is_empty());
+        assert_eq!(
+            replay.warehouse.render(),
+            "##############\n##...[].##..##\n##...@.[]...##\n##....[]....##\n##..........##\n##..........##\n##############"
+        );
+        assert_eq!(replay.warehouse.gps(), 618);
+
+        let end = Warehouse::parse(&replay.warehouse.render());
+        assert_eq!(end.render(), replay.warehouse.render());
+        assert_eq!(end.gps(), 618);
+
+        replay.seek(0);
+        assert_eq!(replay.warehouse.render(), start);
+        assert_eq!(replay.back(), None);
+    }
 }
 
 fn main() {
     let input = include_str!("input.txt");
     let (map, position, moves) = parse(input.lines());
 
-    let result_part1 = process_part1(&map, &position, &moves);
+    if env::args().nth(1).as_deref() == Some("gps") {
+        let scale = env::args().nth(2).map_or(1, |n| n.parse().unwrap());
+        println!("{}", process(&map, &position, &moves, scale));
+        return;
+    }
+
+    if env::args().nth(1).as_deref() == Some("replay") {
+        let arg = |n: usize| env::args().nth(n).map(|a| a.parse().unwrap());
+        let (scale, n) = (arg(2).unwrap_or(2), arg(3).unwrap_or(moves.len()));
+        let mut replay = Replay::new(Warehouse::new(&map, &position, scale), &moves);
+        replay.seek(n);
+        if let Some(step) = replay.log.last() {
+            println!(
+                "move {}: {:?}, {}, boxes {:?}",
+                replay.log.len(),
+                step.direction,
+                if step.success { "moved" } else { "blocked" },
+                step.boxes
+            );
+        }
+        let rendered = replay.warehouse.render();
+        println!("{}", rendered);
+        // read the picture back to check it carries the whole state
+        let parsed = Warehouse::parse(&rendered);
+        assert_eq!(parsed.render(), rendered);
+        println!("gps {}", parsed.gps());
+        return;
+    }
+
+    let result_part1 = process(&map, &position, &moves, 1);
     println!("{}", result_part1);
 
-    let result_part2 = process_part2(&map, &position, &moves);
+    let result_part2 = process(&map, &position, &moves, 2);
     println!("{}", result_part2);
 }
@@ -23,9 +23,16 @@ fn combo(state: &State, operand: usize) -> usize {
     }
 }
 
+fn shift(value: usize, amount: usize) -> usize {
+    u32::try_from(amount)
+        .ok()
+        .and_then(|n| value.checked_shr(n))
+        .unwrap_or(0)
+}
+
 const INSTRUCTIONS: [Instruction; 8] = [
     |state, operand| {
-        let a = state.a >> combo(&state, operand);
+        let a = shift(state.a, combo(&state, operand));
         State { a, ..state }
     },
     |state, operand| {
@@ -58,11 +65,11 @@ const INSTRUCTIONS: [Instruction; 8] = [
         State { output, ..state }
     },
     |state, operand| {
-        let b = state.a >> combo(&state, operand);
+        let b = shift(state.a, combo(&state, operand));
         State { b, ..state }
     },
     |state, operand| {
-        let c = state.a >> combo(&state, operand);
+        let c = shift(state.a, combo(&state, operand));
         State { c, ..state }
     },
 ];
@@ -54,11 +54,10 @@ fn process_part1(blocked: &Vec<(usize, usize)>, grid_size: usize) -> Option<usiz
 }
 
 fn process_part2(ns: &Vec<(usize, usize)>, grid_size: usize) -> (usize, usize) {
-    let i = (1..ns.len())
+    let i = (0..ns.len())
         .rev()
         .find(|&count| process_part1(&ns[0..=count].to_vec(), grid_size).is_some())
-        .unwrap()
-        + 1;
+        .map_or(0, |count| count + 1);
 
     ns[i]
 }
@@ -12,16 +12,10 @@ const NUMERIC_PAD: [&str; 4] = ["789", "456", "123", " 0A"];
 
 const DIRECTIONAL_PAD: [&str; 2] = [" ^A", "<v>"];
 
-fn find_position(key: char, pad: &[&str]) -> Point {
-    let row = pad
-        .iter()
+fn find_position(key: char, pad: &[&str]) -> Option<Point> {
+    pad.iter()
         .enumerate()
-        .find(|(_, row)| row.contains(key))
-        .unwrap()
-        .0;
-    let col = pad[row].find(key).unwrap();
-
-    Point(row, col)
+        .find_map(|(row, keys)| Some(Point(row, keys.find(key)?)))
 }
 
 #[derive(Clone, Copy)]
@@ -86,9 +80,9 @@ fn validate(from: &Point, deltas: &Vec<&Delta>, pad: &[&str]) -> bool {
     true
 }
 
-fn steps(from: char, to: char, pad: &[&str]) -> String {
-    let from_position = find_position(from, pad);
-    let to_position = find_position(to, pad);
+fn steps(from: char, to: char, pad: &[&str]) -> Option<String> {
+    let from_position = find_position(from, pad)?;
+    let to_position = find_position(to, pad)?;
     let delta = to_position - from_position;
     let segments = decompose(delta);
     let mut steps = segments
@@ -103,7 +97,7 @@ fn steps(from: char, to: char, pad: &[&str]) -> String {
         })
         .collect::<Vec<_>>();
     steps.sort_by(compare_steps);
-    steps.iter().find(is_batched).unwrap().clone()
+    Some(steps.iter().find(is_batched).unwrap().clone())
 }
 
 fn compare_steps(a: &String, b: &String) -> Ordering {
@@ -137,7 +131,7 @@ fn is_batched(segment: &&String) -> bool {
     true
 }
 
-fn encode_inner(input: &str, pad: &[&str]) -> String {
+fn encode_inner(input: &str, pad: &[&str]) -> Option<String> {
     (0..input.len() - 1)
         .map(|i| {
             (
@@ -149,10 +143,10 @@ fn encode_inner(input: &str, pad: &[&str]) -> String {
         .collect()
 }
 
-fn encode_inner_memoized(input: &str, &depth: &usize, memo: &mut Memo) -> usize {
+fn encode_inner_memoized(input: &str, &depth: &usize, memo: &mut Memo) -> Option<usize> {
     let prefixed_chunk = prefix(input);
     if depth == 1 {
-        encode_inner(&prefixed_chunk, &DIRECTIONAL_PAD).len()
+        encode_inner(&prefixed_chunk, &DIRECTIONAL_PAD).map(|s| s.len())
     } else {
         (0..prefixed_chunk.len() - 1)
             .map(|i| {
@@ -163,15 +157,15 @@ fn encode_inner_memoized(input: &str, &depth: &usize, memo: &mut Memo) -> usize
             })
             .map(|(a, b)| {
                 if let Some(cached) = memo[depth].get(&(a, b)) {
-                    *cached
+                    Some(*cached)
                 } else {
-                    let result = steps(a, b, &DIRECTIONAL_PAD)
+                    let result = steps(a, b, &DIRECTIONAL_PAD)?
                         .split_inclusive('A')
                         .map(|chunk| encode_inner_memoized(chunk, &(depth - 1), memo))
-                        .sum();
+                        .sum::<Option<usize>>()?;
 
                     memo[depth].insert((a, b), result);
-                    result
+                    Some(result)
                 }
             })
             .sum()
@@ -189,21 +183,22 @@ fn parse(iter: Lines) -> Vec<String> {
 fn process(ns: &Vec<String>, depth: &usize) -> usize {
     let mut memo: Memo = vec![HashMap::new(); depth + 1];
 
+    // a code that isn't digits ending in A, or uses a key the pad lacks, can't be typed
     ns.iter()
-        .map(|n| {
-            let numeric_part = n.strip_suffix('A').unwrap().parse::<usize>().unwrap();
+        .filter_map(|n| {
+            let numeric_part = n.strip_suffix('A')?.parse::<usize>().ok()?;
 
             let length = (0..n.len())
                 .map(|i| prefix(n)[i..=i + 1].to_string())
                 .map(|w| {
-                    encode_inner(&w, &NUMERIC_PAD)
+                    encode_inner(&w, &NUMERIC_PAD)?
                         .split_inclusive('A')
                         .map(|chunk| encode_inner_memoized(chunk, depth, &mut memo))
-                        .sum::<usize>()
+                        .sum::<Option<usize>>()
                 })
-                .sum::<usize>();
+                .sum::<Option<usize>>()?;
 
-            numeric_part * length
+            Some(numeric_part * length)
         })
         .sum()
 }
@@ -228,6 +223,12 @@ mod tests {
         let result = process_part1(&ns);
         assert_eq!(result, 126384);
     }
+
+    #[test]
+    fn test_missing_key() {
+        let ns = parse("+29A\n029A\n0B9A\nA\n029".lines());
+        assert_eq!(process_part1(&ns), 68 * 29);
+    }
 }
 
 fn main() {
@@ -5,9 +5,10 @@ use itertools::Itertools;
 type Pattern = [usize; 5];
 
 fn parse(iter: Lines) -> (Vec<Pattern>, Vec<Pattern>) {
-    iter.chunks(8)
+    iter.chunk_by(|s| s.is_empty())
         .into_iter()
-        .fold((vec![], vec![]), |(mut locks, mut keys), mut next| {
+        .filter(|(blank, _)| !blank)
+        .fold((vec![], vec![]), |(mut locks, mut keys), (_, mut next)| {
             let header = next.next().unwrap();
             let lines = next.take(5).collect::<Vec<_>>();
             let pattern = (0..5)